@@ -16,17 +16,83 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use audit_core::{get_spotify_client, Auditor};
-use clap::{Parser, Subcommand};
+use audit_core::auth::{token_cache_path, verify_scopes, REQUIRED_ENV_VARS};
+use audit_core::output::{
+    render_scan, render_scan_by_artist, render_sync, track_sections_table, OutputError,
+    OutputFormat, Report, Table, Tabulated,
+};
+use audit_core::util::{
+    format_duration, parse_date, parse_market, parse_playlist_url, parse_track_id,
+};
+use audit_core::{
+    get_spotify_client, get_spotify_client_headless, AudioFeatures, AuditDiff, AuditError,
+    AuditSummary, Auditor, AuditorConfig, Market, NaiveDate, PlaylistDetails, PlaylistFilter,
+    PlaylistSortKey, PlaylistTrackCount, ProblematicTrack, ReviewAction, ReviewLogEntry,
+    TrackCache, TrackSummary, PLAYLIST_TRACK_LIMIT,
+};
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
 use dotenvy::dotenv;
-use std::fs::File;
-use std::io::Write;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
 use std::process;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing_subscriber::EnvFilter;
+
+/// Checkpoint file of `sync`, read back by `sync --resume`.
+const SYNC_STATE_FILE: &str = ".sync_state.json";
 
 #[derive(Parser)]
 #[command(name = "spotify-audit")]
 #[command(about = "A tool to audit and manage your Spotify library", long_about = None)]
 struct Cli {
+    /// How many times to retry a request that was rate limited or hit a server error
+    #[arg(long, global = true, default_value_t = 3)]
+    retries: u8,
+
+    /// How many track lookup requests (50 tracks each) to keep in flight at once
+    #[arg(long, global = true, default_value_t = 5, value_name = "N")]
+    max_concurrency: usize,
+
+    /// Pause before every API request, in milliseconds, for apps with a tight rate budget
+    #[arg(long, global = true, default_value_t = 0, value_name = "MS")]
+    request_delay_ms: u64,
+
+    /// Do not read or write the on-disk track cache
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// How long cached track lookups stay valid, in hours
+    #[arg(long, global = true, default_value_t = 24)]
+    cache_ttl: u64,
+
+    /// Output format: table (human-readable), json, csv, tsv or markdown
+    #[arg(
+        short = 'F',
+        long,
+        global = true,
+        default_value = "table",
+        value_parser = PossibleValuesParser::new(OutputFormat::NAMES)
+            .map(|name| name.parse::<OutputFormat>().unwrap_or_default())
+    )]
+    format: OutputFormat,
+
+    /// Log line format; verbosity is controlled with RUST_LOG (e.g., RUST_LOG=debug)
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Pretty)]
+    log_format: LogFormat,
+
+    /// When to use emoji status markers; `auto` uses them only when writing to a terminal
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -35,274 +101,3114 @@ struct Cli {
 enum Commands {
     /// Scans for problematic (unplayable) tracks. By default scans 'Liked Songs'.
     Scan {
-        /// Output the report to a JSON file (e.g., --json=report.json)
+        /// Output the report to a JSON file (e.g., --json=report.json) (deprecated: use -F json)
         #[arg(long)]
         json: Option<String>,
 
-        /// Optional: Scan a specific Playlist ID instead of 'Liked Songs'
+        /// Output the problematic tracks to a CSV file (e.g., --csv=report.csv)
+        #[arg(long)]
+        csv: Option<String>,
+
+        /// Output the report as a Markdown table (e.g., --markdown=report.md)
+        #[arg(long)]
+        markdown: Option<String>,
+
+        /// Optional: Scan a specific Playlist ID instead of 'Liked Songs' ('-' reads it from stdin)
         #[arg(long, short = 'p')]
         playlist: Option<String>,
+
+        /// Scan a playlist by its share URL (e.g., --playlist-url=https://open.spotify.com/playlist/...)
+        #[arg(
+            long,
+            value_name = "URL",
+            conflicts_with_all = ["playlist", "isrc_missing", "no_art", "since"]
+        )]
+        playlist_url: Option<String>,
+
+        /// Compare against a previous JSON report and show fixed/regressed tracks
+        #[arg(long)]
+        compare: Option<String>,
+
+        /// Check availability in this market (two-letter ISO code, e.g., --market=US)
+        #[arg(long, value_parser = parse_market_arg)]
+        market: Option<Market>,
+
+        /// Instead of playability, list 'Liked Songs' that have no ISRC
+        #[arg(long, conflicts_with = "playlist")]
+        isrc_missing: bool,
+
+        /// After the playability report, also list the 'Liked Songs' whose album has no cover art
+        #[arg(long, conflicts_with_all = ["playlist", "isrc_missing"])]
+        no_art: bool,
+
+        /// Instead of playability, report the explicit tracks (reason "Explicit content")
+        #[arg(long, conflicts_with_all = ["isrc_missing", "no_art", "filter_reason", "only_removed", "compare"])]
+        explicit_only: bool,
+
+        /// Append a breakdown of the problems (also added to JSON output as 'stats')
+        #[arg(long)]
+        stats: bool,
+
+        /// Only report one category of problems (the scanned total stays unfiltered)
+        #[arg(long, value_enum, default_value_t = ReasonFilter::All)]
+        filter_reason: ReasonFilter,
+
+        /// Shorthand for --filter-reason=global
+        #[arg(long, conflicts_with = "filter_reason")]
+        only_removed: bool,
+
+        /// Order the problematic tracks (markets: fewest first); default is API order
+        #[arg(long, value_enum)]
+        sort: Option<ScanSort>,
+
+        /// Reverse the --sort order
+        #[arg(long, requires = "sort")]
+        sort_desc: bool,
+
+        /// Show the table report grouped by artist, most problematic tracks first
+        #[arg(long)]
+        group_by_artist: bool,
+
+        /// Stream each problematic track to this file as one JSON object per line (NDJSON)
+        /// while scanning (e.g., --ndjson=problems.ndjson)
+        #[arg(long)]
+        ndjson: Option<String>,
+
+        /// Write every scanned track to this file as an extended M3U playlist, with the
+        /// Spotify URL of each track (e.g., --m3u=playlist.m3u)
+        #[arg(long)]
+        m3u: Option<String>,
+
+        /// Only scan 'Liked Songs' saved on or after this day (UTC), e.g. --since=2025-01-31
+        #[arg(long, value_name = "YYYY-MM-DD", value_parser = parse_date_arg, conflicts_with = "playlist")]
+        since: Option<NaiveDate>,
+
+        /// Stop after scanning this many tracks (the report is marked as truncated)
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+
+        /// After the report, go through the problematic tracks one by one and choose what
+        /// to do with each (with --output, the session log is written there instead of the report;
+        /// table format only)
+        #[arg(long)]
+        interactive: bool,
+
+        /// Write the report in --format to this file instead of stdout
+        #[arg(long, short = 'o')]
+        output: Option<String>,
+    },
+    /// Scans every one of your playlists for problematic tracks
+    ScanAll {
+        /// Write one JSON report per playlist into this directory (e.g., --json=reports/)
+        #[arg(long)]
+        json: Option<String>,
+    },
+    /// Copies your 'Liked Songs' into a new private playlist
+    ExportToPlaylist {
+        /// Name of the playlist to create
+        name: String,
+    },
+    /// Copies a playlist into a new playlist of your own
+    Clone {
+        /// The Spotify ID of the playlist to copy ('-' reads it from stdin)
+        source: String,
+        /// Name of the playlist to create
+        name: String,
+        /// Make the new playlist public (it is private by default)
+        #[arg(long)]
+        public: bool,
+    },
+    /// Appends the tracks of one or more playlists to another playlist
+    Merge {
+        /// Spotify IDs of the source playlists, in order ('-' reads one per line from stdin)
+        #[arg(value_name = "PLAYLIST_ID", required = true, num_args = 1..)]
+        sources: Vec<String>,
+        /// The Spotify ID of the playlist to add the tracks to
+        #[arg(long, value_name = "PLAYLIST_ID")]
+        dest: String,
+        /// Skip tracks already in the destination (or added from an earlier source)
+        #[arg(long)]
+        dedup: bool,
+        /// Also write the merge report to this JSON file
+        #[arg(long)]
+        json: Option<String>,
+    },
+    /// Rescans a playlist (or your Liked Songs) periodically and reports new problems
+    Watch {
+        /// Playlist to watch (defaults to Liked Songs)
+        #[arg(long, short)]
+        playlist: Option<String>,
+        /// Seconds between scans
+        #[arg(long, default_value_t = 3600)]
+        interval: u64,
+    },
+    /// Scans every track of your saved albums for problematic tracks
+    ScanAlbums {
+        /// Output the report to a JSON file (e.g., --json=albums.json)
+        #[arg(long)]
+        json: Option<String>,
     },
     /// Syncs all songs from a specific Playlist to your 'Liked Songs'
     Sync {
-        /// The Spotify ID of the playlist to sync
+        /// The Spotify ID of the playlist to sync ('-' reads it from stdin)
+        #[arg(value_name = "PLAYLIST_ID")]
+        playlist_id: String,
+        /// Output the detailed sync report to a JSON file (deprecated: use -F json)
+        #[arg(long)]
+        json: Option<String>,
+        /// Output the per-track batch results to a CSV file
+        #[arg(long)]
+        csv: Option<String>,
+        /// Preview which tracks would be added without modifying the library
+        #[arg(long)]
+        dry_run: bool,
+        /// Continue an interrupted sync, skipping the batches recorded in .sync_state.json
+        #[arg(long, conflicts_with = "dry_run")]
+        resume: bool,
+        /// Resolve tracks in this market (two-letter ISO code, e.g., --market=US)
+        #[arg(long, value_parser = parse_market_arg)]
+        market: Option<Market>,
+        /// Pause between batches of added tracks, in milliseconds
+        #[arg(long, default_value_t = 100, value_name = "MS")]
+        delay: u64,
+        /// Write the report in --format to this file instead of stdout
+        #[arg(long, short = 'o')]
+        output: Option<String>,
+    },
+    /// Adds every song from your 'Liked Songs' that a playlist is missing to that playlist
+    SyncToPlaylist {
+        /// The Spotify ID of the target playlist ('-' reads it from stdin)
         #[arg(value_name = "PLAYLIST_ID")]
         playlist_id: String,
-        /// Output the detailed sync report to a JSON file
+        /// Output the detailed sync report to a JSON file (deprecated: use -F json)
         #[arg(long)]
         json: Option<String>,
+        /// Pause between batches of added tracks, in milliseconds
+        #[arg(long, default_value_t = 100, value_name = "MS")]
+        delay: u64,
+        /// Write the report in --format to this file instead of stdout
+        #[arg(long, short = 'o')]
+        output: Option<String>,
     },
     /// Lists all your playlists with their IDs
-    List,
+    List {
+        /// Also fetch each playlist's description, snapshot ID, cover image and followers
+        #[arg(long)]
+        full: bool,
+        /// Order the playlists (default: API order, or by name when filtering)
+        #[arg(long, visible_alias = "sort-by", value_enum)]
+        sort: Option<ListSort>,
+        /// Reverse the --sort order (e.g., smallest playlists first)
+        #[arg(long, requires = "sort")]
+        reverse: bool,
+        /// Only list playlists you own (hides followed and others' collaborative playlists)
+        #[arg(long, visible_alias = "mine-only")]
+        owned: bool,
+        /// Only list collaborative playlists
+        #[arg(long)]
+        collaborative: bool,
+        /// Only list public playlists
+        #[arg(long)]
+        public: bool,
+        /// Add a column with each playlist's description (cut to 60 characters)
+        #[arg(long, short = 'v')]
+        verbose: bool,
+    },
+    /// Prints how many tracks a playlist holds, without fetching them
+    Count {
+        /// The Spotify ID of the playlist ('-' reads it from stdin)
+        playlist: String,
+    },
+    /// Summarizes 'Liked Songs': playability, artists, albums, local files and decades
+    Stats,
+    /// Shows the authenticated Spotify account (ID, name, email, country, subscription)
+    #[command(visible_alias = "whoami")]
+    Me,
+    /// Shows how many songs you saved to 'Liked Songs' per month
+    Timeline,
+    /// Ranks artists by how many of your 'Liked Songs' they lead (or, with --credited, appear on)
+    #[command(visible_alias = "artists")]
+    ArtistStats {
+        /// Only show the N most-represented artists (e.g., --top=20)
+        #[arg(long)]
+        top: Option<u32>,
+        /// Count every artist credited on a track, not only the first-listed one
+        #[arg(long)]
+        credited: bool,
+        /// Output the full ranked artist list to a JSON file (deprecated: use -F json)
+        #[arg(long)]
+        json: Option<String>,
+    },
+    /// Compares the artists you follow with the artists of your 'Liked Songs'
+    FollowedArtists,
+    /// Lists the 'Liked Songs' that are in none of your playlists
+    Orphans,
+    /// Lists the albums of which your 'Liked Songs' hold only some tracks
+    AlbumCompleteness,
     /// Inspects a specific track ID to retrieve full forensic metadata
     Inspect {
-        /// The Spotify Track ID to inspect
-        #[arg(value_name = "TRACK_ID")]
-        track_id: String,
+        /// The Spotify Track ID to inspect ('-' reads one or more from stdin, one per line)
+        #[arg(value_name = "TRACK_ID", required_unless_present = "from_file")]
+        track_id: Option<String>,
+        /// Check availability in this market (two-letter ISO code, e.g., --market=US)
+        #[arg(long, value_parser = parse_market_arg, conflicts_with = "from_file")]
+        market: Option<Market>,
+        /// Inspect every ID in this file (one per line) and print a JSON array
+        #[arg(long, conflicts_with = "track_id")]
+        from_file: Option<String>,
+        /// Also fetch the audio features (tempo, key, energy, ...) of the track
+        #[arg(long, conflicts_with_all = ["market", "from_file"])]
+        with_features: bool,
+        /// Save the forensics to this file, as JSON unless --format says otherwise
+        #[arg(long, short = 'o')]
+        output: Option<String>,
+    },
+    /// Compares two playlists and shows which tracks are unique to each and shared
+    Compare {
+        /// The first playlist (ID, URI or URL)
+        #[arg(value_name = "PLAYLIST_A")]
+        playlist_a: String,
+        /// The second playlist (ID, URI or URL)
+        #[arg(value_name = "PLAYLIST_B")]
+        playlist_b: String,
+        /// Output the full comparison to a JSON file (deprecated: use -F json)
+        #[arg(long)]
+        json: Option<String>,
+        /// List every track of each section, not just the counts
+        #[arg(long, short = 'v')]
+        verbose: bool,
+    },
+    /// Finds songs (same ISRC) that appear in several playlists under different track IDs
+    IsrcDuplicates {
+        /// Output the clusters to a JSON file (deprecated: use -F json)
+        #[arg(long)]
+        json: Option<String>,
+    },
+    /// Shows what differs between a playlist and your 'Liked Songs' (by ID and ISRC)
+    Diff {
+        /// The playlist to compare (ID, URI or URL; '-' reads it from stdin)
+        #[arg(value_name = "PLAYLIST_ID")]
+        playlist_id: String,
+        /// Output the full comparison to a JSON file (deprecated: use -F json)
+        #[arg(long)]
+        json: Option<String>,
+        /// List every track of each section, not just the counts
+        #[arg(long, short = 'v')]
+        verbose: bool,
+    },
+    /// Lists liked songs missing from a playlist and/or playlist tracks not yet liked (by ID)
+    Gap {
+        /// The "full library" playlist (ID, URI or URL; '-' reads it from stdin)
+        #[arg(value_name = "PLAYLIST_ID")]
+        playlist_id: String,
+        /// Which side of the gap to list
+        #[arg(long, value_enum, default_value_t = GapDirection::Both)]
+        direction: GapDirection,
+    },
+    /// Inspects several track IDs at once (batched 50 per request)
+    InspectBatch {
+        /// Spotify Track IDs, URIs or URLs, separated by spaces ('-' reads one per line from stdin)
+        #[arg(value_name = "TRACK_ID", required = true, num_args = 1..)]
+        track_ids: Vec<String>,
+        /// Output the full metadata of every track to a JSON file (deprecated: use -F json)
+        #[arg(long)]
+        json: Option<String>,
+    },
+    /// Removes unplayable tracks from a playlist (globally removed only, by default)
+    RemoveDead {
+        /// The Spotify ID of the playlist to clean
+        #[arg(long, short = 'p')]
+        playlist: String,
+        /// Also remove geo-locked tracks (still available in other markets)
+        #[arg(long)]
+        geo_locked: bool,
+    },
+    /// Removes every unplayable track (geo-locked or removed globally) from a playlist.
+    /// Previews only, unless --confirm is given.
+    PurgePlaylist {
+        /// The Spotify ID of the playlist to purge
+        #[arg(value_name = "PLAYLIST_ID")]
+        playlist_id: String,
+        /// Actually delete the tracks (without it, nothing is modified)
+        #[arg(long)]
+        confirm: bool,
+    },
+    /// Combines several JSON scan reports into one (no Spotify login needed)
+    MergeReports {
+        /// The JSON reports to merge (e.g., from 'scan --json')
+        #[arg(value_name = "FILE", required = true, num_args = 1..)]
+        files: Vec<String>,
+        /// Path of the merged JSON report to write
+        #[arg(long, short = 'o')]
+        output: String,
+    },
+    /// Exports all your 'Liked Songs' to a JSON backup file
+    Backup {
+        /// Path of the JSON file to write (e.g., --output=backup.json)
+        #[arg(long, short = 'o')]
+        output: String,
+    },
+    /// Lists the tracks of a 'backup' file that were playable then but are unplayable now
+    ScanDelta {
+        /// JSON file written by the 'backup' command
+        #[arg(value_name = "FILE")]
+        backup: String,
+        /// Also write the newly unavailable tracks to this JSON file
+        #[arg(long)]
+        json: Option<String>,
+    },
+    /// Removes specific tracks from your 'Liked Songs'
+    Remove {
+        /// Spotify IDs, URIs or URLs of the tracks to remove ('-' reads them from stdin)
+        #[arg(required = true)]
+        track_ids: Vec<String>,
+        /// Only validate the IDs and print what would be removed
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Re-adds the tracks of a JSON backup file to your 'Liked Songs'
+    Restore {
+        /// JSON file to restore from: a 'backup' export or a 'scan' report
+        #[arg(value_name = "FILE")]
+        file: String,
+        /// Preview which tracks would be re-liked without modifying the library
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Prints a shell completion script (no Spotify login needed)
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: Shell,
+        /// Write the script to this file instead of stdout
+        #[arg(long, short = 'o')]
+        output: Option<String>,
+    },
+    /// Deletes the on-disk track cache
+    ClearCache,
+    /// Checks the environment, authentication, token scopes and API reachability
+    Doctor,
+    /// Deduplicates 'Liked Songs' (or a playlist) by removing dead tracks that share an ISRC with a living track.
+    Dedup {
+        /// Show which tracks would be removed without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Optional: Deduplicate a specific Playlist ID instead of 'Liked Songs'
+        #[arg(long, short = 'p')]
+        playlist: Option<String>,
+        /// Report 'Liked Songs' with the same artists and name but different IDs (removes nothing)
+        #[arg(long, conflicts_with_all = ["playlist", "dry_run"])]
+        name_match: bool,
+        /// Name similarity needed for --name-match, from 0.0 to 1.0 (1.0 = exact)
+        #[arg(long, default_value_t = 1.0, requires = "name_match", value_parser = parse_similarity_arg)]
+        similarity: f32,
+        /// Instead of removing anything, search a playable release (same ISRC) for each
+        /// liked track removed globally (use -F json for machine-readable output)
+        #[arg(long, conflicts_with_all = ["playlist", "dry_run", "name_match"])]
+        suggest_replacements: bool,
+        /// Remove the duplicates without asking for confirmation (for scripts)
+        #[arg(long, short = 'y', conflicts_with_all = ["dry_run", "name_match", "suggest_replacements"])]
+        yes: bool,
     },
-    /// Deduplicates 'Liked Songs' by removing dead tracks that share an ISRC with a living track.
-    Dedup,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum LogFormat {
+    /// Human-readable log lines
+    Pretty,
+    /// One JSON object per line, for log pipelines
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ColorChoice {
+    /// Emoji markers when stdout is a terminal, ASCII tags otherwise
+    Auto,
+    /// Always use emoji markers
+    Always,
+    /// Always use ASCII tags
+    Never,
+}
+
+static COLOR: OnceLock<ColorChoice> = OnceLock::new();
+
+/// Whether track statuses written to `terminal` (or not) should carry emoji markers.
+fn colored(terminal: bool) -> bool {
+    match COLOR.get().copied().unwrap_or(ColorChoice::Auto) {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => terminal,
+    }
+}
+
+/// Whether track statuses printed to stdout should carry emoji markers.
+fn stdout_colored() -> bool {
+    colored(io::stdout().is_terminal())
+}
+
+/// Installs the tracing subscriber. Logs go to stderr so they never mix with reports.
+fn init_logging(format: LogFormat) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("error"));
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    match format {
+        LogFormat::Pretty => builder.init(),
+        LogFormat::Json => builder.json().init(),
+    }
 }
 
 #[tokio::main]
 async fn main() {
-    env_logger::init();
-
     if dotenv().is_err() {
         // Silently ignore
     }
 
     let cli = Cli::parse();
+    init_logging(cli.log_format);
+    let _ = COLOR.set(cli.color);
 
     match &cli.command {
-        Commands::Scan { json, playlist } => {
-            handle_scan(json.as_deref(), playlist.as_deref()).await;
+        Commands::Completions { shell, output } => {
+            handle_completions(*shell, output.as_deref());
+        }
+        Commands::ClearCache => {
+            handle_clear_cache();
+        }
+        Commands::Doctor => {
+            handle_doctor().await;
+        }
+        Commands::MergeReports { files, output } => {
+            handle_merge_reports(files, output);
+        }
+        Commands::Scan {
+            json,
+            csv,
+            markdown,
+            playlist,
+            playlist_url,
+            compare,
+            market,
+            isrc_missing,
+            no_art,
+            explicit_only,
+            stats,
+            filter_reason,
+            only_removed,
+            sort,
+            sort_desc,
+            group_by_artist,
+            ndjson,
+            m3u,
+            since,
+            limit,
+            interactive,
+            output,
+        } => {
+            warn_deprecated_json(json);
+            // The review prompts share stdout with the report.
+            if *interactive && cli.format != OutputFormat::Table {
+                Cli::command()
+                    .error(
+                        clap::error::ErrorKind::ArgumentConflict,
+                        "--interactive only works with the table format (drop -F/--format)",
+                    )
+                    .exit();
+            }
+            if *isrc_missing {
+                handle_isrc_missing(auditor_config(&cli)).await;
+            } else {
+                // In interactive mode --output (or the deprecated --json) receives the
+                // session log, not the report.
+                let (report_output, report_json) = if *interactive {
+                    (&None, &None)
+                } else {
+                    (output, json)
+                };
+                let mut files = report_files(cli.format, report_output, report_json, csv);
+                if let Some(path) = markdown {
+                    files.push((OutputFormat::Markdown, path.clone()));
+                }
+                let playlist = playlist.as_deref().map(id_arg);
+                handle_scan(
+                    auditor_config(&cli),
+                    ScanTarget {
+                        playlist_id: playlist.as_deref(),
+                        playlist_url: playlist_url.as_deref(),
+                        market: *market,
+                        ndjson: ndjson.as_deref(),
+                        m3u: m3u.as_deref(),
+                        since: *since,
+                        limit: *limit,
+                        explicit_only: *explicit_only,
+                    },
+                    compare.as_deref(),
+                    ScanView {
+                        with_stats: *stats,
+                        reason: if *only_removed {
+                            ReasonFilter::Global
+                        } else {
+                            *filter_reason
+                        },
+                        sort: sort.map(|key| (key, *sort_desc)),
+                        group_by_artist: *group_by_artist,
+                        review: interactive.then(|| output.as_deref().or(json.as_deref())),
+                    },
+                    stdout_format(cli.format, report_output),
+                    &files,
+                )
+                .await;
+                if *no_art {
+                    handle_no_art(
+                        auditor_config(&cli),
+                        stdout_format(cli.format, report_output),
+                    )
+                    .await;
+                }
+            }
+        }
+        Commands::ExportToPlaylist { name } => {
+            handle_export_to_playlist(auditor_config(&cli), name).await;
+        }
+        Commands::Clone {
+            source,
+            name,
+            public,
+        } => {
+            handle_clone(auditor_config(&cli), &id_arg(source), name, *public).await;
+        }
+        Commands::Merge {
+            sources,
+            dest,
+            dedup,
+            json,
+        } => {
+            warn_deprecated_json(json);
+            handle_merge(
+                auditor_config(&cli),
+                cli.format,
+                &id_args(sources),
+                &id_arg(dest),
+                *dedup,
+                json.as_deref(),
+            )
+            .await;
+        }
+        Commands::Watch { playlist, interval } => {
+            let playlist = playlist.as_deref().map(id_arg);
+            handle_watch(
+                auditor_config(&cli),
+                cli.format,
+                playlist.as_deref(),
+                *interval,
+            )
+            .await;
+        }
+        Commands::ScanAlbums { json } => {
+            let files = report_files(cli.format, &None, json, &None);
+            handle_scan_albums(auditor_config(&cli), cli.format, &files).await;
+        }
+        Commands::ScanAll { json } => {
+            handle_scan_all(auditor_config(&cli), cli.format, json.as_deref()).await;
+        }
+        Commands::Sync {
+            playlist_id,
+            json,
+            csv,
+            dry_run,
+            resume,
+            market,
+            delay,
+            output,
+        } => {
+            warn_deprecated_json(json);
+            let files = report_files(cli.format, output, json, csv);
+            handle_sync(
+                AuditorConfig {
+                    batch_delay_ms: *delay,
+                    ..auditor_config(&cli)
+                },
+                &id_arg(playlist_id),
+                *market,
+                *dry_run,
+                *resume,
+                stdout_format(cli.format, output),
+                &files,
+            )
+            .await;
+        }
+        Commands::SyncToPlaylist {
+            playlist_id,
+            json,
+            delay,
+            output,
+        } => {
+            warn_deprecated_json(json);
+            let files = report_files(cli.format, output, json, &None);
+            handle_sync_to_playlist(
+                AuditorConfig {
+                    batch_delay_ms: *delay,
+                    ..auditor_config(&cli)
+                },
+                &id_arg(playlist_id),
+                stdout_format(cli.format, output),
+                &files,
+            )
+            .await;
+        }
+        Commands::List {
+            full,
+            sort,
+            reverse,
+            owned,
+            collaborative,
+            public,
+            verbose,
+        } => {
+            let filter = PlaylistFilter {
+                owned_only: *owned,
+                collaborative_only: *collaborative,
+                public_only: *public,
+            };
+            handle_list(
+                auditor_config(&cli),
+                cli.format,
+                *full,
+                sort.map(|key| (PlaylistSortKey::from(key), *reverse)),
+                filter,
+                *verbose,
+            )
+            .await;
+        }
+        Commands::Orphans => {
+            handle_orphans(auditor_config(&cli), cli.format).await;
+        }
+        Commands::Count { playlist } => {
+            handle_count(auditor_config(&cli), cli.format, &id_arg(playlist)).await;
+        }
+        Commands::Stats => {
+            handle_stats(auditor_config(&cli), cli.format).await;
+        }
+        Commands::Me => {
+            handle_me(auditor_config(&cli), cli.format).await;
+        }
+        Commands::Timeline => {
+            handle_timeline(auditor_config(&cli), cli.format).await;
+        }
+        Commands::AlbumCompleteness => {
+            handle_album_completeness(auditor_config(&cli), cli.format).await;
+        }
+        Commands::FollowedArtists => {
+            handle_followed_artists(auditor_config(&cli), cli.format).await;
+        }
+        Commands::ArtistStats {
+            top,
+            credited,
+            json,
+        } => {
+            warn_deprecated_json(json);
+            handle_artist_stats(
+                auditor_config(&cli),
+                cli.format,
+                *top,
+                *credited,
+                json.as_deref(),
+            )
+            .await;
+        }
+        Commands::Inspect {
+            track_id,
+            market,
+            from_file,
+            with_features,
+            output,
+        } => {
+            // The file gets the machine format; the table is only for the terminal.
+            let file = output.as_deref().map(|path| {
+                let format = match cli.format {
+                    OutputFormat::Table => OutputFormat::Json,
+                    other => other,
+                };
+                (format, path)
+            });
+            let ids = match (track_id.as_deref(), from_file) {
+                (_, Some(path)) => read_ids_from_file(path),
+                (Some("-"), None) => id_args(&["-".to_string()]),
+                (Some(id), None) => vec![id.to_string()],
+                (None, None) => unreachable!("clap requires TRACK_ID or --from-file"),
+            };
+            match ids.as_slice() {
+                [id] if from_file.is_none() => {
+                    handle_inspect(
+                        auditor_config(&cli),
+                        stdout_format(cli.format, output),
+                        id,
+                        *market,
+                        *with_features,
+                        file,
+                        cache_ttl(&cli),
+                    )
+                    .await;
+                }
+                _ if market.is_some() => {
+                    eprintln!("[ERROR] --market only applies when inspecting a single track");
+                    process::exit(1);
+                }
+                _ if *with_features => {
+                    eprintln!(
+                        "[ERROR] --with-features only applies when inspecting a single track"
+                    );
+                    process::exit(1);
+                }
+                _ => {
+                    handle_inspect_many(
+                        auditor_config(&cli),
+                        cli.format,
+                        &ids,
+                        file,
+                        cache_ttl(&cli),
+                    )
+                    .await
+                }
+            }
+        }
+        Commands::Compare {
+            playlist_a,
+            playlist_b,
+            json,
+            verbose,
+        } => {
+            warn_deprecated_json(json);
+            handle_compare(
+                auditor_config(&cli),
+                cli.format,
+                &id_arg(playlist_a),
+                &id_arg(playlist_b),
+                json.as_deref(),
+                *verbose,
+            )
+            .await;
+        }
+        Commands::Diff {
+            playlist_id,
+            json,
+            verbose,
+        } => {
+            warn_deprecated_json(json);
+            handle_diff(
+                auditor_config(&cli),
+                cli.format,
+                &id_arg(playlist_id),
+                json.as_deref(),
+                *verbose,
+            )
+            .await;
+        }
+        Commands::Gap {
+            playlist_id,
+            direction,
+        } => {
+            handle_gap(
+                auditor_config(&cli),
+                cli.format,
+                &id_arg(playlist_id),
+                *direction,
+            )
+            .await;
         }
-        Commands::Sync { playlist_id, json } => {
-            handle_sync(playlist_id, json.as_deref()).await;
+        Commands::IsrcDuplicates { json } => {
+            warn_deprecated_json(json);
+            handle_isrc_duplicates(auditor_config(&cli), cli.format, json.as_deref()).await;
         }
-        Commands::List => {
-            handle_list().await;
+        Commands::InspectBatch { track_ids, json } => {
+            warn_deprecated_json(json);
+            handle_inspect_batch(
+                auditor_config(&cli),
+                cli.format,
+                &id_args(track_ids),
+                json.as_deref(),
+                cache_ttl(&cli),
+            )
+            .await;
+        }
+        Commands::RemoveDead {
+            playlist,
+            geo_locked,
+        } => {
+            handle_remove_dead(auditor_config(&cli), &id_arg(playlist), *geo_locked).await;
+        }
+        Commands::PurgePlaylist {
+            playlist_id,
+            confirm,
+        } => {
+            handle_purge_playlist(auditor_config(&cli), &id_arg(playlist_id), *confirm).await;
+        }
+        Commands::Backup { output } => {
+            handle_backup(auditor_config(&cli), output).await;
+        }
+        Commands::ScanDelta { backup, json } => {
+            handle_scan_delta(auditor_config(&cli), cli.format, backup, json.as_deref()).await;
+        }
+        Commands::Remove { track_ids, dry_run } => {
+            handle_remove(auditor_config(&cli), &id_args(track_ids), *dry_run).await;
+        }
+        Commands::Restore { file, dry_run } => {
+            handle_restore(auditor_config(&cli), file, *dry_run).await;
+        }
+        Commands::Dedup {
+            dry_run,
+            playlist,
+            name_match,
+            similarity,
+            suggest_replacements,
+            yes,
+        } => {
+            if *suggest_replacements {
+                handle_suggest_replacements(auditor_config(&cli), cli.format).await;
+            } else if *name_match {
+                handle_dedup_by_name(auditor_config(&cli), *similarity).await;
+            } else {
+                let playlist = playlist.as_deref().map(id_arg);
+                handle_dedup(auditor_config(&cli), *dry_run, *yes, playlist.as_deref()).await;
+            }
+        }
+    }
+}
+
+/// Reads a single ID from stdin: the first non-empty line, trimmed.
+fn read_id_from_stdin() -> Result<String, std::io::Error> {
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        let id = line.trim();
+        if !id.is_empty() {
+            return Ok(id.to_string());
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "no ID found on stdin",
+    ))
+}
+
+/// Reads one ID per line from stdin until EOF, skipping blank lines.
+fn read_ids_from_stdin() -> Result<Vec<String>, std::io::Error> {
+    read_ids(io::stdin().lock())
+}
+
+/// Reads one ID per line from `reader` until EOF, skipping blank lines.
+fn read_ids(reader: impl BufRead) -> Result<Vec<String>, std::io::Error> {
+    let mut ids = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let id = line.trim();
+        if !id.is_empty() {
+            ids.push(id.to_string());
+        }
+    }
+    Ok(ids)
+}
+
+/// Resolves an ID argument, reading it from stdin when it is `-`.
+fn id_arg(arg: &str) -> String {
+    if arg != "-" {
+        return arg.to_string();
+    }
+    read_id_from_stdin().unwrap_or_else(|e| {
+        eprintln!("[ERROR] Failed to read ID from stdin: {}", e);
+        process::exit(1);
+    })
+}
+
+/// Resolves a list of ID arguments; a `-` entry expands to every ID on stdin.
+fn id_args(args: &[String]) -> Vec<String> {
+    let mut ids = Vec::new();
+    for arg in args {
+        if arg == "-" {
+            match read_ids_from_stdin() {
+                Ok(stdin_ids) => ids.extend(stdin_ids),
+                Err(e) => {
+                    eprintln!("[ERROR] Failed to read IDs from stdin: {}", e);
+                    process::exit(1);
+                }
+            }
+        } else {
+            ids.push(arg.clone());
+        }
+    }
+    ids
+}
+
+/// Reads the IDs listed in `path`, one per line.
+fn read_ids_from_file(path: &str) -> Vec<String> {
+    File::open(path)
+        .and_then(|file| read_ids(io::BufReader::new(file)))
+        .unwrap_or_else(|e| {
+            eprintln!("[ERROR] Failed to read IDs from '{}': {}", path, e);
+            process::exit(1);
+        })
+}
+
+/// Writes the completion script for `shell`, covering every subcommand and flag.
+fn handle_completions(shell: Shell, output_path: Option<&str>) {
+    let mut command = Cli::command();
+    // Complete the installed binary, not the display name from `#[command(name)]`.
+    let bin_name = env!("CARGO_BIN_NAME");
+
+    match output_path {
+        Some(path) => match File::create(path) {
+            Ok(mut file) => {
+                clap_complete::generate(shell, &mut command, bin_name, &mut file);
+                println!("[SAVED] {} completions saved to: {}", shell, path);
+            }
+            Err(e) => {
+                eprintln!("[ERROR] Failed to create file '{}': {}", path, e);
+                process::exit(1);
+            }
+        },
+        None => clap_complete::generate(shell, &mut command, bin_name, &mut io::stdout()),
+    }
+}
+
+fn auditor_config(cli: &Cli) -> AuditorConfig {
+    AuditorConfig {
+        max_concurrency: cli.max_concurrency,
+        max_retries: cli.retries,
+        request_delay_ms: cli.request_delay_ms,
+        ..AuditorConfig::default()
+    }
+}
+
+/// TTL of the track cache, or `None` when `--no-cache` is set.
+fn cache_ttl(cli: &Cli) -> Option<Duration> {
+    (!cli.no_cache).then(|| Duration::from_secs(cli.cache_ttl.saturating_mul(3600)))
+}
+
+/// Attaches the on-disk track cache to `auditor` when caching is enabled.
+fn with_track_cache(auditor: Auditor, ttl: Option<Duration>) -> Auditor {
+    match (ttl, TrackCache::default_path()) {
+        (Some(ttl), Some(path)) => auditor.with_cache(TrackCache::open(path, ttl)),
+        _ => auditor,
+    }
+}
+
+fn handle_merge_reports(files: &[String], output_path: &str) {
+    let mut summaries = Vec::with_capacity(files.len());
+    for path in files {
+        match load_summary(path) {
+            Ok(summary) => summaries.push(summary),
+            Err(e) => {
+                eprintln!("[ERROR] Failed to load report '{}': {}", path, e);
+                process::exit(1);
+            }
         }
-        Commands::Inspect { track_id } => {
-            handle_inspect(track_id).await;
+    }
+
+    let merged = AuditSummary::merge(summaries);
+    println!("Merged {} reports.", files.len());
+    println!("Total Tracks Scanned: {}", merged.total_tracks_scanned);
+    println!("Problematic Tracks:   {}", merged.problematic_tracks.len());
+    if let Some(error) = &merged.scan_error {
+        println!("Scan Errors:          {}", error);
+    }
+    save_json(OutputFormat::Table, output_path, &merged);
+}
+
+fn handle_clear_cache() {
+    let Some(path) = TrackCache::default_path() else {
+        eprintln!("[ERROR] Could not determine the cache directory (HOME is not set).");
+        process::exit(1);
+    };
+    match TrackCache::clear(&path) {
+        Ok(true) => println!("[CLEANUP] Removed track cache: {}", path.display()),
+        Ok(false) => println!("[OK] No track cache at {}", path.display()),
+        Err(e) => {
+            eprintln!("[ERROR] Failed to remove '{}': {}", path.display(), e);
+            process::exit(1);
         }
-        Commands::Dedup => {
-            handle_dedup().await;
+    }
+}
+
+/// Prints one doctor check and, when it failed, how to fix it. Returns `passed`.
+fn doctor_check(passed: bool, label: &str, fix: &str) -> bool {
+    if passed {
+        println!("✓ {}", label);
+    } else {
+        println!("✗ {}", label);
+        println!("   Fix: {}", fix);
+    }
+    passed
+}
+
+/// Runs every setup check and exits with code 2 when any of them fails.
+async fn handle_doctor() {
+    println!("Checking your setup...");
+    println!();
+    let mut healthy = true;
+
+    let mut env_ok = true;
+    for var in REQUIRED_ENV_VARS {
+        let set = std::env::var(var).is_ok_and(|value| !value.trim().is_empty());
+        env_ok &= doctor_check(
+            set,
+            &format!("{} is set", var),
+            &format!("add {}=... to your .env file or environment", var),
+        );
+    }
+    healthy &= env_ok;
+
+    let reachable = ("api.spotify.com", 443)
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .is_some_and(|addr| TcpStream::connect_timeout(&addr, Duration::from_secs(5)).is_ok());
+    healthy &= doctor_check(
+        reachable,
+        "api.spotify.com is reachable",
+        "check your network connection, proxy or firewall",
+    );
+
+    if env_ok && reachable {
+        let client = if io::stdin().is_terminal() {
+            get_spotify_client().await
+        } else {
+            get_spotify_client_headless().await
+        };
+        match client {
+            Ok(spotify) => {
+                doctor_check(true, "Authenticated with Spotify", "");
+                match verify_scopes(&spotify).await {
+                    Ok(missing) => {
+                        healthy &= doctor_check(
+                            missing.is_empty(),
+                            "Token has every required scope",
+                            &format!(
+                                "missing {}; delete {} and authorize again",
+                                missing.join(", "),
+                                token_cache_path().display()
+                            ),
+                        );
+                    }
+                    Err(e) => {
+                        healthy &= doctor_check(
+                            false,
+                            &format!("Token works for API calls ({})", e),
+                            &format!(
+                                "delete {} and authorize again",
+                                token_cache_path().display()
+                            ),
+                        );
+                    }
+                }
+            }
+            Err(e) => {
+                healthy &= doctor_check(
+                    false,
+                    &format!("Authenticated with Spotify ({})", e),
+                    "check the client ID/secret and that the redirect URI matches your app settings",
+                );
+            }
         }
+    } else {
+        println!("- Skipped authentication checks (fix the problems above first)");
+        healthy = false;
+    }
+
+    println!();
+    if healthy {
+        println!("[OK] Everything looks good.");
+    } else {
+        println!("[FAILED] Some checks failed.");
+        process::exit(2);
+    }
+}
+
+fn parse_market_arg(code: &str) -> Result<Market, String> {
+    parse_market(code).ok_or_else(|| {
+        format!(
+            "'{}' is not a valid two-letter ISO country code (e.g., US, MX, DE)",
+            code
+        )
+    })
+}
+
+fn parse_date_arg(date: &str) -> Result<NaiveDate, String> {
+    parse_date(date).ok_or_else(|| format!("'{}' is not a valid date (use YYYY-MM-DD)", date))
+}
+
+fn parse_similarity_arg(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(threshold) if (0.0..=1.0).contains(&threshold) => Ok(threshold),
+        _ => Err(format!("'{}' is not a number between 0.0 and 1.0", value)),
     }
 }
 
-async fn get_auditor() -> Auditor {
-    let spotify = match get_spotify_client().await {
+/// Authenticates, prompting for authorization only when stdin is a terminal: under
+/// cron, CI or a pipe nobody can paste the redirect URL, so fail instead of hanging.
+async fn get_auditor(config: AuditorConfig) -> Auditor {
+    let client = if io::stdin().is_terminal() {
+        get_spotify_client().await
+    } else {
+        get_spotify_client_headless().await
+    };
+    let spotify = match client {
         Ok(s) => s,
         Err(e) => {
-            eprintln!("Error initializing Spotify client: {}", e);
+            eprintln!("Error initializing Spotify client: {}", e);
+            process::exit(1);
+        }
+    };
+    Auditor::with_config(spotify, config)
+}
+
+/// The format printed to stdout: the human report whenever `--output` takes the
+/// formatted one, so a file export never hides the summary.
+fn stdout_format(format: OutputFormat, output: &Option<String>) -> OutputFormat {
+    if output.is_some() {
+        OutputFormat::Table
+    } else {
+        format
+    }
+}
+
+/// Collects the report files to write: `--output` in `--format`, plus the
+/// `--json` and `--csv` shortcuts.
+fn report_files(
+    format: OutputFormat,
+    output: &Option<String>,
+    json: &Option<String>,
+    csv: &Option<String>,
+) -> Vec<(OutputFormat, String)> {
+    let mut files = Vec::new();
+    if let Some(path) = output {
+        files.push((format, path.clone()));
+    }
+    if let Some(path) = json {
+        files.push((OutputFormat::Json, path.clone()));
+    }
+    if let Some(path) = csv {
+        files.push((OutputFormat::Csv, path.clone()));
+    }
+    files
+}
+
+/// Warns that the per-command `--json` flag was replaced by the global `-F json`.
+/// The flag keeps working until it is removed.
+fn warn_deprecated_json(json: &Option<String>) {
+    if json.is_some() {
+        eprintln!("[WARN] --json is deprecated; use the global -F json (with --output where available) instead.");
+    }
+}
+
+/// Prints a status line to stdout, or to stderr when stdout carries a
+/// machine-readable report that must stay parseable.
+fn status(stdout: OutputFormat, message: &str) {
+    if stdout == OutputFormat::Table {
+        println!("{}", message);
+    } else {
+        eprintln!("{}", message);
+    }
+}
+
+/// Exits with code 3 and the wait Spotify asked for when `e` is a rate limit.
+fn exit_if_rate_limited(e: &AuditError) {
+    if let AuditError::RateLimit { retry_after_secs } = e {
+        eprintln!();
+        eprintln!(
+            "[RATE LIMITED] Spotify says wait {}s before retrying.",
+            retry_after_secs
+        );
+        process::exit(3);
+    }
+}
+
+/// Prints `report` in `format` and returns `true`, unless `format` is the
+/// human-readable table, which each command lays out itself.
+fn print_report(format: OutputFormat, report: &dyn Report) -> bool {
+    if format == OutputFormat::Table {
+        return false;
+    }
+    match format.render(report) {
+        Ok(content) => print!("{}", content),
+        Err(e) => {
+            eprintln!("[ERROR] Failed to render report: {}", e);
+            process::exit(1);
+        }
+    }
+    true
+}
+
+/// Writes `report` to `path` in `format`. Unlike `emit_report`, a file that cannot be
+/// written is fatal, for commands whose only product is that file.
+fn save_report_or_exit(format: OutputFormat, path: &str, report: &dyn Report) {
+    let written = format
+        .render(report)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| Ok(fs::write(path, content)?));
+    if let Err(e) = written {
+        eprintln!("[ERROR] Failed to write report to '{}': {}", path, e);
+        process::exit(1);
+    }
+    eprintln!("[SAVED] Report saved to: {}", path);
+}
+
+/// Prints the report rendered for stdout, then writes every requested file.
+fn emit_report<F>(stdout: OutputFormat, files: &[(OutputFormat, String)], render: F)
+where
+    F: Fn(OutputFormat, bool) -> Result<String, OutputError>,
+{
+    match render(stdout, stdout_colored()) {
+        Ok(content) => {
+            if stdout == OutputFormat::Table {
+                println!();
+            }
+            print!("{}", content);
+        }
+        Err(e) => eprintln!("[ERROR] Failed to render report: {}", e),
+    }
+
+    for (format, path) in files {
+        let written = render(*format, colored(false))
+            .map_err(anyhow::Error::from)
+            .and_then(|content| Ok(fs::write(path, content)?));
+        match written {
+            Ok(()) => {
+                status(stdout, "");
+                status(stdout, &format!("[SAVED] Report saved to: {}", path));
+            }
+            Err(e) => {
+                eprintln!();
+                eprintln!("[ERROR] Failed to write report to '{}': {}", path, e);
+            }
+        }
+    }
+}
+
+/// Wires a stderr progress bar into the auditor's scan progress callback.
+/// Starts as a spinner and switches to a bar once the total is known.
+fn attach_progress_bar(auditor: &mut Auditor) -> ProgressBar {
+    let bar = ProgressBar::new_spinner();
+    bar.set_style(
+        ProgressStyle::with_template("{spinner} {pos} tracks scanned")
+            .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+    );
+
+    let callback_bar = bar.clone();
+    auditor.set_progress_callback(Box::new(move |scanned, total| {
+        if total > 0 && callback_bar.length() != Some(total as u64) {
+            callback_bar.set_length(total as u64);
+            callback_bar.set_style(
+                ProgressStyle::with_template("[{bar:40}] {pos}/{len} tracks ({eta})")
+                    .unwrap_or_else(|_| ProgressStyle::default_bar()),
+            );
+        }
+        callback_bar.set_position(scanned as u64);
+    }));
+
+    bar
+}
+
+async fn handle_remove_dead(config: AuditorConfig, playlist_id: &str, include_geo_locked: bool) {
+    let auditor = get_auditor(config).await;
+    println!(
+        "Removing {} tracks from Playlist ID: {} ...",
+        if include_geo_locked {
+            "unplayable"
+        } else {
+            "globally removed"
+        },
+        playlist_id
+    );
+
+    match auditor
+        .remove_unplayable_from_playlist(playlist_id, !include_geo_locked, false)
+        .await
+    {
+        Ok(removed) => {
+            println!();
+            if removed.is_empty() {
+                println!("[OK] No dead tracks found. Nothing was removed.");
+            } else {
+                println!("[CLEANUP] Removed {} tracks:", removed.len());
+                for track in &removed {
+                    println!("   - {}", track.format_with_color(stdout_colored()));
+                }
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Removal failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_purge_playlist(config: AuditorConfig, playlist_id: &str, confirm: bool) {
+    let auditor = get_auditor(config).await;
+    if confirm {
+        println!(
+            "Purging unplayable tracks from Playlist ID: {} ...",
+            playlist_id
+        );
+    } else {
+        println!(
+            "[DRY RUN] Previewing purge of unplayable tracks from Playlist ID: {} ...",
+            playlist_id
+        );
+    }
+
+    match auditor
+        .remove_unplayable_from_playlist(playlist_id, false, !confirm)
+        .await
+    {
+        Ok(removed) => {
+            println!();
+            if removed.is_empty() {
+                println!("[OK] No unplayable tracks found. Nothing to purge.");
+            } else if confirm {
+                println!("[CLEANUP] Removed {} tracks:", removed.len());
+                for track in &removed {
+                    println!("   - {} - {}", track.name, track.artists);
+                }
+            } else {
+                println!("[DRY RUN] {} tracks would be removed:", removed.len());
+                for track in &removed {
+                    println!("   - {} - {}", track.name, track.artists);
+                }
+                println!();
+                println!("(Nothing was deleted. Run with --confirm to apply).");
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Purge failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_backup(config: AuditorConfig, output_path: &str) {
+    let auditor = get_auditor(config).await;
+    println!("Backing up Liked Songs...");
+
+    match auditor.backup_liked_songs(output_path).await {
+        Ok(count) => {
+            println!();
+            println!("[SAVED] Backed up {} tracks to: {}", count, output_path);
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Backup failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_scan_delta(
+    config: AuditorConfig,
+    format: OutputFormat,
+    backup_path: &str,
+    json_path: Option<&str>,
+) {
+    let auditor = get_auditor(config).await;
+    status(
+        format,
+        &format!(
+            "Checking the tracks of '{}' against Spotify...",
+            backup_path
+        ),
+    );
+
+    match auditor.get_recently_unavailable(backup_path).await {
+        Ok(tracks) => {
+            if !print_report(format, &tracks) {
+                println!();
+                if tracks.is_empty() {
+                    println!("[OK] No track became unplayable since the backup.");
+                } else {
+                    println!(
+                        "[FOUND] {} tracks became unplayable since the backup:",
+                        tracks.len()
+                    );
+                    for (i, track) in tracks.iter().enumerate() {
+                        println!("{}. {}", i + 1, track.format_with_color(stdout_colored()));
+                    }
+                }
+            }
+            if let Some(path) = json_path {
+                save_json(format, path, &tracks);
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Backup comparison failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_remove(config: AuditorConfig, track_ids: &[String], dry_run: bool) {
+    if dry_run {
+        let invalid: Vec<&String> = track_ids
+            .iter()
+            .filter(|id| parse_track_id(id).is_none())
+            .collect();
+        if !invalid.is_empty() {
+            for id in invalid {
+                eprintln!("[ERROR] Invalid Track ID: {}", id);
+            }
+            process::exit(1);
+        }
+        println!(
+            "[DRY RUN] {} tracks would be removed from Liked Songs:",
+            track_ids.len()
+        );
+        for id in track_ids {
+            println!("   - {}", id);
+        }
+        return;
+    }
+
+    let auditor = get_auditor(config).await;
+    let ids: Vec<&str> = track_ids.iter().map(String::as_str).collect();
+    match auditor.unsave_tracks(&ids).await {
+        Ok(removed) => println!("[CLEANUP] Removed {} tracks from Liked Songs.", removed),
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Remove failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_restore(config: AuditorConfig, path: &str, dry_run: bool) {
+    let auditor = get_auditor(config).await;
+    if dry_run {
+        println!("[DRY RUN] Previewing restore from {} ...", path);
+    } else {
+        println!("Restoring tracks from {} to Liked Songs...", path);
+    }
+
+    match auditor.restore_liked_from_file(path, dry_run).await {
+        Ok(report) => {
+            println!();
+            println!("---------------------------------------------------");
+            if report.dry_run {
+                println!("RESTORE PREVIEW (DRY RUN - library not modified)");
+            } else {
+                println!("RESTORE COMPLETE");
+            }
+            println!("---------------------------------------------------");
+            println!("Initial Liked Songs:      {}", report.initial_liked_count);
+            println!(
+                "Tracks in File:           {}",
+                report.total_tracks_in_playlist
+            );
+            println!("Tracks Processed:         {}", report.tracks_processed);
+            println!("Skipped (Already Liked):  {}", report.skipped_already_liked);
+            println!("Final Liked Songs:        {}", report.final_liked_count);
+            println!("---------------------------------------------------");
+            if report.dry_run {
+                println!("Tracks That Would Be Added: {}", report.estimated_added);
+            } else {
+                println!("Estimated New Tracks Added: {}", report.estimated_added);
+                println!("Confirmed Added (delta):    {}", report.confirmed_added);
+            }
+            println!("Failed Tracks:              {}", report.failed_tracks.len());
+            println!("---------------------------------------------------");
+
+            for id in &report.failed_tracks {
+                eprintln!("   [FAILED] {}", id);
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Restore failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_dedup(config: AuditorConfig, dry_run: bool, yes: bool, playlist_id: Option<&str>) {
+    let auditor = get_auditor(config).await;
+    let target = match playlist_id {
+        Some(pid) => format!("Playlist ID: {}", pid),
+        None => "Liked Songs".to_string(),
+    };
+    if dry_run {
+        println!("[DRY RUN] Previewing Deduplication of {}...", target);
+    } else {
+        println!("Starting Deduplication of {}...", target);
+    }
+    println!("This will fetch every track to find ID conflicts. Please wait.");
+
+    let plan = match playlist_id {
+        Some(pid) => auditor.plan_playlist_dedup(pid).await,
+        None => auditor.plan_liked_dedup().await,
+    };
+    let plan = plan.unwrap_or_else(|e| exit_dedup_failed(&e));
+
+    println!();
+    if plan.is_empty() {
+        println!("[OK] No safe duplicates found. Nothing to clean up.");
+        return;
+    }
+    if dry_run {
+        println!(
+            "[DRY RUN] {} dead duplicate tracks would be removed:",
+            plan.len()
+        );
+    } else {
+        println!("{} dead duplicate tracks can be removed:", plan.len());
+    }
+    for candidate in &plan {
+        println!("   - {}", candidate.label);
+    }
+    println!();
+    if dry_run {
+        println!("(Nothing was deleted. Run without --dry-run to apply).");
+        return;
+    }
+    if !yes && !confirm(&format!("Remove these {} tracks?", plan.len())) {
+        println!("Aborted. Nothing was deleted.");
+        return;
+    }
+
+    let result = match playlist_id {
+        Some(pid) => auditor.execute_playlist_dedup(pid, &plan).await,
+        None => auditor.execute_liked_dedup(&plan).await,
+    };
+    if let Err(e) = result {
+        exit_dedup_failed(&e);
+    }
+    println!("[CLEANUP] Removed {} dead duplicate tracks.", plan.len());
+    println!("(Note: We kept the playable versions of these tracks safe).");
+}
+
+fn exit_dedup_failed(e: &AuditError) -> ! {
+    exit_if_rate_limited(e);
+    eprintln!();
+    eprintln!("Deduplication failed: {}", e);
+    process::exit(1);
+}
+
+/// Asks a yes/no question on stdin. Only `y` or `yes` count as yes; end of input is a no.
+fn confirm(question: &str) -> bool {
+    print!("{} [y/N]: ", question);
+    let _ = io::stdout().flush();
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => {
+            println!();
+            false
+        }
+        Ok(_) => matches!(line.trim().to_ascii_lowercase().as_str(), "y" | "yes"),
+    }
+}
+
+/// What a scan reads, and where it streams problems while it runs.
+struct ScanTarget<'a> {
+    /// Playlist to scan; `None` (without `playlist_url`) scans Liked Songs.
+    playlist_id: Option<&'a str>,
+    /// Share URL of the playlist to scan, instead of `playlist_id`.
+    playlist_url: Option<&'a str>,
+    market: Option<Market>,
+    /// NDJSON file receiving each problematic track as it is found.
+    ndjson: Option<&'a str>,
+    /// M3U file receiving every scanned track.
+    m3u: Option<&'a str>,
+    /// Only scan Liked Songs saved on or after this day.
+    since: Option<NaiveDate>,
+    /// Maximum number of tracks to scan.
+    limit: Option<usize>,
+    /// Report explicit tracks instead of unplayable ones.
+    explicit_only: bool,
+}
+
+/// How a finished scan is presented.
+#[derive(Clone, Copy)]
+struct ScanView<'a> {
+    /// Append the `AuditStats` breakdown.
+    with_stats: bool,
+    /// Which problem category to keep.
+    reason: ReasonFilter,
+    /// Sort key and whether to reverse it; `None` keeps the API order.
+    sort: Option<(ScanSort, bool)>,
+    /// Lay the table report out per artist instead of per track.
+    group_by_artist: bool,
+    /// Review the tracks interactively after the report, writing the session log to
+    /// the inner path (if any).
+    review: Option<Option<&'a str>>,
+}
+
+/// Sort orders selectable with `list --sort`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ListSort {
+    /// Playlist name
+    Name,
+    /// Number of tracks, largest first
+    Tracks,
+    /// Owner name, then playlist name
+    Owner,
+}
+
+impl From<ListSort> for PlaylistSortKey {
+    fn from(sort: ListSort) -> Self {
+        match sort {
+            ListSort::Name => PlaylistSortKey::Name,
+            ListSort::Tracks => PlaylistSortKey::TrackCount,
+            ListSort::Owner => PlaylistSortKey::Owner,
+        }
+    }
+}
+
+/// Sides of the library gap selectable with `gap --direction`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum GapDirection {
+    /// Liked songs that are not in the playlist
+    LikedOnly,
+    /// Playlist tracks that are not liked
+    PlaylistOnly,
+    /// Both lists
+    Both,
+}
+
+/// Sort keys selectable with `scan --sort`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ScanSort {
+    /// Track name
+    Name,
+    /// Artist names
+    Artist,
+    /// Number of available markets (0 first, the most urgent)
+    Markets,
+    /// Technical reason, then markets
+    Reason,
+}
+
+impl ScanSort {
+    fn apply(self, tracks: &mut [ProblematicTrack], descending: bool) {
+        // Track name breaks ties so the order (and the saved files) are deterministic.
+        tracks.sort_by(|a, b| {
+            let primary = match self {
+                ScanSort::Name => Ordering::Equal,
+                ScanSort::Artist => a.artists.to_lowercase().cmp(&b.artists.to_lowercase()),
+                ScanSort::Markets => a.available_markets_count.cmp(&b.available_markets_count),
+                ScanSort::Reason => a
+                    .reason
+                    .cmp(&b.reason)
+                    .then(a.available_markets_count.cmp(&b.available_markets_count)),
+            };
+            primary
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        if descending {
+            tracks.reverse();
+        }
+    }
+}
+
+/// Problem categories selectable with `scan --filter-reason`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ReasonFilter {
+    /// Removed globally (0 markets)
+    Global,
+    /// Geo-locked (unplayable here, available in other markets)
+    Geo,
+    /// Every problematic track
+    All,
+}
+
+impl ReasonFilter {
+    fn apply(self, summary: AuditSummary) -> AuditSummary {
+        match self {
+            ReasonFilter::Global => summary.filter_globally_removed(),
+            ReasonFilter::Geo => summary.filter_geo_locked(),
+            ReasonFilter::All => summary,
+        }
+    }
+}
+
+async fn handle_suggest_replacements(config: AuditorConfig, format: OutputFormat) {
+    let auditor = get_auditor(config).await;
+    status(
+        format,
+        "Searching replacements for Liked Songs removed from Spotify...",
+    );
+
+    match auditor.suggest_isrc_replacements().await {
+        Ok(suggestions) => {
+            let rows = suggestions
+                .iter()
+                .map(|s| {
+                    let replacement = s.replacement.as_ref();
+                    vec![
+                        s.dead.id.clone(),
+                        s.dead.name.clone(),
+                        s.dead.artists.join(", "),
+                        s.dead.isrc.clone().unwrap_or_default(),
+                        replacement.map_or(String::new(), |r| r.id.clone()),
+                        replacement.map_or(String::new(), |r| r.name.clone()),
+                        replacement
+                            .and_then(|r| r.external_urls.get("spotify").cloned())
+                            .unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            let table = Table {
+                headers: vec![
+                    "dead_id",
+                    "dead_name",
+                    "dead_artists",
+                    "isrc",
+                    "replacement_id",
+                    "replacement_name",
+                    "replacement_url",
+                ],
+                rows,
+            };
+            if print_report(format, &Tabulated::new(&suggestions, table)) {
+                return;
+            }
+
+            println!();
+            if suggestions.is_empty() {
+                println!("[OK] No liked track is removed globally without a live copy.");
+                return;
+            }
+            for suggestion in &suggestions {
+                println!("[DEAD] {}", suggestion.dead);
+                match &suggestion.replacement {
+                    Some(track) => println!(
+                        "   Suggested replacement: {} - {} ({})",
+                        track.name,
+                        track.artists.join(", "),
+                        track
+                            .external_urls
+                            .get("spotify")
+                            .map(String::as_str)
+                            .unwrap_or("-")
+                    ),
+                    None => println!("   No playable release found for this ISRC."),
+                }
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Replacement search failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_dedup_by_name(config: AuditorConfig, similarity: f32) {
+    let auditor = get_auditor(config).await;
+    println!(
+        "Searching Liked Songs for same-name duplicates (similarity >= {:.2})...",
+        similarity
+    );
+
+    match auditor.find_duplicate_tracks_by_name(similarity).await {
+        Ok(groups) => {
+            println!();
+            if groups.is_empty() {
+                println!("[OK] No same-name duplicates found.");
+                return;
+            }
+
+            println!("[FOUND] {} groups of possible duplicates:", groups.len());
+            for group in &groups {
+                println!();
+                println!(
+                    "{} - {}",
+                    group.tracks[0].name,
+                    group.tracks[0].artists.join(", ")
+                );
+                for track in &group.tracks {
+                    println!(
+                        "   - {} | {} | {} markets | ISRC {}",
+                        track.id,
+                        track.album,
+                        track.available_markets.len(),
+                        track
+                            .external_ids
+                            .get("isrc")
+                            .map(String::as_str)
+                            .unwrap_or("-")
+                    );
+                }
+            }
+            println!();
+            println!(
+                "(Nothing was deleted. Review the groups and remove the extra copies yourself)."
+            );
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("Deduplication failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_scan(
+    config: AuditorConfig,
+    target: ScanTarget<'_>,
+    compare_path: Option<&str>,
+    view: ScanView<'_>,
+    stdout: OutputFormat,
+    files: &[(OutputFormat, String)],
+) {
+    // Load the previous report first so a bad path fails before the (long) scan.
+    let previous = compare_path.map(|path| match load_summary(path) {
+        Ok(summary) => summary,
+        Err(e) => {
+            eprintln!("[ERROR] Failed to load previous report '{}': {}", path, e);
+            process::exit(1);
+        }
+    });
+
+    let mut auditor = get_auditor(config).await;
+    if let Some(path) = target.ndjson {
+        match File::create(path) {
+            Ok(file) => auditor.set_problem_writer(Box::new(io::LineWriter::new(file))),
+            Err(e) => {
+                eprintln!("[ERROR] Failed to create file '{}': {}", path, e);
+                process::exit(1);
+            }
+        }
+    }
+    if let Some(path) = target.m3u {
+        let result = File::create(path)
+            .map_err(AuditError::from)
+            .and_then(|file| auditor.set_m3u_writer(Box::new(io::BufWriter::new(file))));
+        if let Err(e) = result {
+            eprintln!("[ERROR] Failed to write M3U file '{}': {}", path, e);
+            process::exit(1);
+        }
+    }
+    auditor.set_explicit_only(target.explicit_only);
+    let progress = attach_progress_bar(&mut auditor);
+
+    // The scanned playlist, as an ID that interactive removals can use.
+    let playlist_id = target.playlist_id.map(String::from).or_else(|| {
+        target
+            .playlist_url
+            .and_then(parse_playlist_url)
+            .map(|id| id.to_string())
+    });
+    let scan_result = if let Some(url) = target.playlist_url {
+        status(
+            stdout,
+            &format!("Starting scan of Playlist URL: {} ...", url),
+        );
+        auditor
+            .scan_playlist_by_url(url, target.market, target.limit)
+            .await
+    } else if let Some(pid) = target.playlist_id {
+        status(
+            stdout,
+            &format!("Starting scan of Playlist ID: {} ...", pid),
+        );
+        auditor
+            .scan_playlist(pid, target.market, target.limit)
+            .await
+    } else {
+        status(stdout, "Starting scan of Liked Songs...");
+        auditor
+            .scan_liked_songs(target.market, target.limit, target.since)
+            .await
+    };
+    progress.finish_and_clear();
+
+    match scan_result {
+        Ok(summary) => {
+            // Filter both sides so a category change never shows up as fixed/regressed.
+            let mut summary = view.reason.apply(summary);
+            if let Some((key, descending)) = view.sort {
+                key.apply(&mut summary.problematic_tracks, descending);
+            }
+            let previous = previous.map(|p| view.reason.apply(p));
+            let target = if playlist_id.is_some() {
+                "Playlist"
+            } else {
+                "Liked Songs"
+            };
+            emit_report(stdout, files, |format, colored| {
+                if view.group_by_artist && format == OutputFormat::Table {
+                    Ok(render_scan_by_artist(&summary, target, colored))
+                } else {
+                    render_scan(&summary, target, format, view.with_stats, colored)
+                }
+            });
+
+            // The diff is a human-only addendum; keep machine output parseable.
+            if stdout == OutputFormat::Table {
+                if let Some(previous) = &previous {
+                    print_audit_diff(&Auditor::diff_audits(previous, &summary));
+                }
+            }
+
+            if let Some(log_path) = view.review {
+                let log = review_tracks(
+                    &auditor,
+                    playlist_id.as_deref(),
+                    &summary.problematic_tracks,
+                )
+                .await;
+                println!();
+                println!(
+                    "Reviewed {} of {} problematic tracks.",
+                    log.len(),
+                    summary.problematic_tracks.len()
+                );
+                if let Some(path) = log_path {
+                    save_json(stdout, path, &log);
+                }
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("Audit failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Asks what to do with each of `tracks` in turn, until they run out or the user quits
+/// (`q` or end of input). Failed actions are logged with their error and the review goes on.
+/// Removing takes the track out of `playlist_id` when the scan was of a playlist, else
+/// out of Liked Songs.
+async fn review_tracks(
+    auditor: &Auditor,
+    playlist_id: Option<&str>,
+    tracks: &[ProblematicTrack],
+) -> Vec<ReviewLogEntry> {
+    let source = if playlist_id.is_some() {
+        "the playlist"
+    } else {
+        "Liked Songs"
+    };
+    let mut log = Vec::new();
+    for (i, track) in tracks.iter().enumerate() {
+        println!();
+        println!(
+            "[{}/{}] {}",
+            i + 1,
+            tracks.len(),
+            track.format_with_color(stdout_colored())
+        );
+        println!("   {}", track.external_url);
+        let Some(action) = read_review_action(source) else {
+            break;
+        };
+
+        let mut entry = ReviewLogEntry {
+            track_id: track.id.clone(),
+            name: track.name.clone(),
+            artists: track.artists.clone(),
+            action,
+            replacement: None,
+            error: None,
+        };
+        match action {
+            ReviewAction::Skip => {}
+            ReviewAction::Remove => {
+                match remove_reviewed_track(auditor, playlist_id, &track.id).await {
+                    Ok(()) => println!("[REMOVED] '{}' is no longer in {}.", track.name, source),
+                    Err(e) => {
+                        eprintln!("[ERROR] Remove failed: {}", e);
+                        entry.error = Some(e.to_string());
+                    }
+                }
+            }
+            ReviewAction::FindReplacement => {
+                match auditor.find_replacement_for_track(&track.id).await {
+                    Ok(Some(replacement)) => {
+                        println!(
+                            "   Suggested replacement: {} - {} ({})",
+                            replacement.name,
+                            replacement.artists.join(", "),
+                            replacement
+                                .external_urls
+                                .get("spotify")
+                                .map(String::as_str)
+                                .unwrap_or("-")
+                        );
+                        entry.replacement = Some(replacement);
+                    }
+                    Ok(None) => println!("   No playable release found for this recording."),
+                    Err(e) => {
+                        eprintln!("[ERROR] Replacement search failed: {}", e);
+                        entry.error = Some(e.to_string());
+                    }
+                }
+            }
+        }
+        log.push(entry);
+    }
+    log
+}
+
+/// Removes a reviewed track from the scanned playlist, or from Liked Songs.
+async fn remove_reviewed_track(
+    auditor: &Auditor,
+    playlist_id: Option<&str>,
+    track_id: &str,
+) -> Result<(), AuditError> {
+    match playlist_id {
+        Some(playlist_id) => auditor.remove_playlist_track(playlist_id, track_id).await,
+        None => auditor.remove_liked_track(track_id).await,
+    }
+}
+
+/// Shows the review menu until a valid choice is read. `None` means quit, either
+/// chosen or because stdin ended.
+fn read_review_action(source: &str) -> Option<ReviewAction> {
+    loop {
+        print!(
+            "[s]kip / [r]emove from {} / [f]ind replacement / [q]uit: ",
+            source
+        );
+        let _ = io::stdout().flush();
+
+        let mut line = String::new();
+        match io::stdin().lock().read_line(&mut line) {
+            Ok(0) | Err(_) => {
+                println!();
+                return None;
+            }
+            Ok(_) => {}
+        }
+        match line.trim().to_ascii_lowercase().as_str() {
+            "s" => return Some(ReviewAction::Skip),
+            "r" => return Some(ReviewAction::Remove),
+            "f" => return Some(ReviewAction::FindReplacement),
+            "q" => return None,
+            _ => println!("Please answer s, r, f or q."),
+        }
+    }
+}
+
+/// The `scan --no-art` addendum to the playability report. It is printed as status
+/// lines, so a machine-readable report on stdout stays parseable.
+async fn handle_no_art(config: AuditorConfig, stdout: OutputFormat) {
+    let auditor = get_auditor(config).await;
+    status(stdout, "");
+    status(
+        stdout,
+        "Searching Liked Songs for albums without cover art...",
+    );
+
+    match auditor.find_tracks_without_album_art().await {
+        Ok(tracks) => {
+            status(stdout, "");
+            if tracks.is_empty() {
+                status(stdout, "[OK] Every liked track has album art.");
+                return;
+            }
+            for track in &tracks {
+                status(stdout, &format!("   - {}", track));
+            }
+            status(stdout, "");
+            status(
+                stdout,
+                &format!("Found {} tracks without album art.", tracks.len()),
+            );
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("Audit failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_orphans(config: AuditorConfig, format: OutputFormat) {
+    let auditor = get_auditor(config).await;
+    status(
+        format,
+        "Comparing Liked Songs with the tracks of all your playlists...",
+    );
+
+    match auditor.find_orphan_liked_tracks().await {
+        Ok(tracks) => {
+            if print_report(format, &tracks) {
+                return;
+            }
+            println!();
+            if tracks.is_empty() {
+                println!("[OK] Every liked track is in at least one playlist.");
+                return;
+            }
+            for track in &tracks {
+                println!("   - {}", track);
+            }
+            println!();
+            println!("Found {} liked tracks in no playlist.", tracks.len());
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Orphan search failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_isrc_missing(config: AuditorConfig) {
+    let auditor = get_auditor(config).await;
+    println!("Searching Liked Songs for tracks without an ISRC...");
+
+    match auditor.find_isrc_less_tracks().await {
+        Ok(tracks) => {
+            println!();
+            if tracks.is_empty() {
+                println!("[OK] Every liked track has an ISRC.");
+                return;
+            }
+
+            println!(
+                "{:<36} | {:<30} | {:<30} | {:<4} | {:<5}",
+                "ID", "Name", "Album", "Disc", "Track"
+            );
+            println!(
+                "{:-<36}-+-{:-<30}-+-{:-<30}-+-{:-<4}-+-{:-<5}",
+                "", "", "", "", ""
+            );
+            for track in &tracks {
+                println!(
+                    "{:<36} | {:<30} | {:<30} | {:<4} | {:<5}",
+                    track.id,
+                    truncate(&track.name, 30),
+                    truncate(&track.album, 30),
+                    track.disc_number,
+                    track.track_number
+                );
+            }
+            println!();
+            println!("Found {} tracks without an ISRC.", tracks.len());
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("Audit failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Shortens `text` to at most `max` characters, marking the cut with "..".
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() > max {
+        let kept: String = text.chars().take(max.saturating_sub(2)).collect();
+        format!("{}..", kept)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Writes `value` as pretty JSON to `path` and reports the outcome as status lines,
+/// so a machine-readable report on `stdout` stays parseable.
+fn save_json<T: Serialize>(stdout: OutputFormat, path: &str, value: &T) {
+    let result = serde_json::to_string_pretty(value)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| Ok(fs::write(path, content)?));
+    match result {
+        Ok(()) => {
+            status(stdout, "");
+            status(stdout, &format!("[SAVED] Report saved to: {}", path));
+        }
+        Err(e) => {
+            eprintln!();
+            eprintln!("[ERROR] Failed to write report to '{}': {}", path, e);
+        }
+    }
+}
+
+fn load_summary(path: &str) -> anyhow::Result<AuditSummary> {
+    let content = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn print_audit_diff(diff: &AuditDiff) {
+    println!();
+    println!("---------------------------------------------------");
+    println!("CHANGES SINCE PREVIOUS REPORT");
+    println!("---------------------------------------------------");
+    println!("Fixed (now playable):   {}", diff.fixed.len());
+    println!("Regressed (new issues): {}", diff.regressed.len());
+
+    if !diff.fixed.is_empty() {
+        println!();
+        println!("Fixed:");
+        for track in &diff.fixed {
+            println!("  + {}", track.format_with_color(stdout_colored()));
+        }
+    }
+    if !diff.regressed.is_empty() {
+        println!();
+        println!("Regressed:");
+        for track in &diff.regressed {
+            println!("  - {}", track.format_with_color(stdout_colored()));
+        }
+    }
+}
+
+async fn handle_export_to_playlist(config: AuditorConfig, name: &str) {
+    let auditor = get_auditor(config).await;
+    println!("Copying your Liked Songs into a new playlist '{}'...", name);
+
+    match auditor.liked_to_new_playlist(name).await {
+        Ok(playlist_id) => {
+            println!();
+            println!("[OK] Created playlist: {}", playlist_id);
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Export failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_clone(config: AuditorConfig, source_id: &str, name: &str, public: bool) {
+    let auditor = get_auditor(config).await;
+    let source = match auditor.get_playlist_details(source_id).await {
+        Ok(details) => details.summary,
+        Err(e) => exit_clone_failed(&e),
+    };
+    println!(
+        "Copying '{}' ({} tracks) into a new {} playlist '{}'...",
+        source.name,
+        source.total_tracks,
+        if public { "public" } else { "private" },
+        name
+    );
+    if source.total_tracks as usize > PLAYLIST_TRACK_LIMIT {
+        eprintln!(
+            "[WARN] Playlists hold at most {} tracks; only the first {} will be copied.",
+            PLAYLIST_TRACK_LIMIT, PLAYLIST_TRACK_LIMIT
+        );
+    }
+
+    match auditor.clone_playlist(source_id, name, public).await {
+        Ok(playlist_id) => {
+            println!();
+            println!("[OK] Created playlist: {}", playlist_id);
+        }
+        Err(e) => exit_clone_failed(&e),
+    }
+}
+
+fn exit_clone_failed(e: &AuditError) -> ! {
+    exit_if_rate_limited(e);
+    eprintln!();
+    eprintln!("[ERROR] Clone failed: {}", e);
+    process::exit(1);
+}
+
+async fn handle_merge(
+    config: AuditorConfig,
+    format: OutputFormat,
+    sources: &[String],
+    dest: &str,
+    dedup: bool,
+    json_path: Option<&str>,
+) {
+    let auditor = get_auditor(config).await;
+    status(
+        format,
+        &format!("Merging {} playlists into {}...", sources.len(), dest),
+    );
+
+    let source_ids: Vec<&str> = sources.iter().map(String::as_str).collect();
+    match auditor.merge_playlists(&source_ids, dest, dedup).await {
+        Ok(report) => {
+            if !print_report(format, &report) {
+                println!();
+                for source in &report.sources {
+                    if dedup {
+                        println!(
+                            "   - {}: {} added, {} skipped",
+                            source.playlist_id, source.added, source.skipped
+                        );
+                    } else {
+                        println!("   - {}: {} added", source.playlist_id, source.added);
+                    }
+                }
+                println!();
+                if dedup {
+                    println!(
+                        "[OK] Added {} tracks, skipped {} already present.",
+                        report.total_added, report.total_skipped
+                    );
+                } else {
+                    println!("[OK] Added {} tracks.", report.total_added);
+                }
+            }
+            if let Some(path) = json_path {
+                save_json(format, path, &report);
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Merge failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_watch(
+    config: AuditorConfig,
+    format: OutputFormat,
+    playlist_id: Option<&str>,
+    interval: u64,
+) {
+    let auditor = get_auditor(config).await;
+    let target = match playlist_id {
+        Some(id) => format!("Playlist {}", id),
+        None => "Liked Songs".to_string(),
+    };
+    status(
+        format,
+        &format!(
+            "Watching {} every {}s. Press Ctrl-C to stop.",
+            target, interval
+        ),
+    );
+
+    let report_target = target.clone();
+    let callback = Box::new(move |delta: AuditSummary| {
+        match render_scan(&delta, &report_target, format, false, stdout_colored()) {
+            Ok(content) => print!("{}", content),
+            Err(e) => eprintln!("[ERROR] Failed to render report: {}", e),
+        }
+    });
+
+    tokio::select! {
+        result = auditor.watch_playlist(playlist_id, interval, callback) => {
+            if let Err(e) = result {
+                exit_if_rate_limited(&e);
+                eprintln!();
+                eprintln!("[ERROR] Watch failed: {}", e);
+                process::exit(1);
+            }
+        }
+        _ = tokio::signal::ctrl_c() => {
+            status(format, &format!("Stopped watching {}.", target));
+        }
+    }
+}
+
+async fn handle_scan_albums(
+    config: AuditorConfig,
+    stdout: OutputFormat,
+    files: &[(OutputFormat, String)],
+) {
+    let mut auditor = get_auditor(config).await;
+    let progress = attach_progress_bar(&mut auditor);
+    status(stdout, "Starting scan of your saved albums...");
+
+    let result = auditor.audit_saved_albums().await;
+    progress.finish_and_clear();
+
+    match result {
+        Ok(summary) => {
+            emit_report(stdout, files, |format, colored| {
+                render_scan(&summary, "Saved Albums", format, false, colored)
+            });
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("Audit failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_scan_all(config: AuditorConfig, format: OutputFormat, json_dir: Option<&str>) {
+    let auditor = get_auditor(config).await;
+    status(format, "Starting scan of all your playlists...");
+
+    match auditor.scan_all_playlists().await {
+        Ok(results) => {
+            // Sorted by playlist ID, in every format.
+            let sorted: BTreeMap<&String, &AuditSummary> = results.iter().collect();
+            let failed = sorted.values().filter(|s| s.scan_error.is_some()).count();
+            let total_problematic: usize =
+                sorted.values().map(|s| s.problematic_tracks.len()).sum();
+            let scan_status = |summary: &AuditSummary| {
+                if summary.scan_error.is_some() {
+                    "FAILED"
+                } else {
+                    "OK"
+                }
+            };
+
+            let table = Table {
+                headers: vec!["id", "scanned", "problems", "status"],
+                rows: sorted
+                    .iter()
+                    .map(|(id, summary)| {
+                        vec![
+                            id.to_string(),
+                            summary.total_tracks_scanned.to_string(),
+                            summary.problematic_tracks.len().to_string(),
+                            scan_status(summary).to_string(),
+                        ]
+                    })
+                    .collect(),
+            };
+            if !print_report(format, &Tabulated::new(&sorted, table)) {
+                println!();
+                println!(
+                    "{:<25} | {:<8} | {:<8} | {:<6}",
+                    "ID", "Scanned", "Problems", "Status"
+                );
+                println!("{:-<25}-+-{:-<8}-+-{:-<8}-+-{:-<6}", "", "", "", "");
+                for (id, summary) in &sorted {
+                    println!(
+                        "{:<25} | {:<8} | {:<8} | {:<6}",
+                        id,
+                        summary.total_tracks_scanned,
+                        summary.problematic_tracks.len(),
+                        scan_status(summary)
+                    );
+                }
+
+                println!();
+                println!("Playlists Scanned:  {}", sorted.len());
+                println!("Failed Scans:       {}", failed);
+                println!("Total Problematic:  {}", total_problematic);
+            }
+
+            for (id, summary) in &sorted {
+                if let Some(e) = &summary.scan_error {
+                    eprintln!("[ERROR] {}: {}", id, e);
+                }
+            }
+
+            if let Some(dir) = json_dir {
+                if let Err(e) = fs::create_dir_all(dir) {
+                    eprintln!("[ERROR] Failed to create directory '{}': {}", dir, e);
+                    return;
+                }
+                for (id, summary) in &results {
+                    let path = Path::new(dir).join(format!("{}.json", id));
+                    let json_content = serde_json::to_string_pretty(summary).unwrap_or_default();
+                    if let Err(e) = fs::write(&path, json_content) {
+                        eprintln!("[ERROR] Failed to write report '{}': {}", path.display(), e);
+                    }
+                }
+                status(format, "");
+                status(format, &format!("[SAVED] Reports saved to: {}", dir));
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("Audit failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_sync(
+    config: AuditorConfig,
+    playlist_id: &str,
+    market: Option<Market>,
+    dry_run: bool,
+    resume: bool,
+    stdout: OutputFormat,
+    files: &[(OutputFormat, String)],
+) {
+    let auditor = get_auditor(config).await.with_sync_state(SYNC_STATE_FILE);
+
+    if dry_run {
+        status(
+            stdout,
+            &format!(
+                "[DRY RUN] Previewing sync of Playlist ID: {} to Liked Songs...",
+                playlist_id
+            ),
+        );
+    } else if resume {
+        status(
+            stdout,
+            &format!(
+                "Resuming sync of Playlist ID: {} to Liked Songs...",
+                playlist_id
+            ),
+        );
+    } else {
+        status(
+            stdout,
+            &format!("Syncing Playlist ID: {} to Liked Songs...", playlist_id),
+        );
+    }
+
+    match auditor
+        .sync_playlist_to_liked(playlist_id, market, dry_run, resume)
+        .await
+    {
+        Ok(report) => {
+            emit_report(stdout, files, |format, _| render_sync(&report, format));
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Sync failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_sync_to_playlist(
+    config: AuditorConfig,
+    playlist_id: &str,
+    stdout: OutputFormat,
+    files: &[(OutputFormat, String)],
+) {
+    let auditor = get_auditor(config).await;
+    status(
+        stdout,
+        &format!("Syncing Liked Songs to Playlist ID: {}...", playlist_id),
+    );
+
+    match auditor.sync_liked_to_playlist(playlist_id).await {
+        Ok(report) => {
+            emit_report(stdout, files, |format, _| render_sync(&report, format));
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Sync failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_me(config: AuditorConfig, format: OutputFormat) {
+    let auditor = get_auditor(config).await;
+
+    match auditor.get_user_profile().await {
+        Ok(profile) => {
+            let fields = [
+                ("ID", profile.id.clone()),
+                (
+                    "Display Name",
+                    profile.display_name.clone().unwrap_or_default(),
+                ),
+                ("Email", profile.email.clone().unwrap_or_default()),
+                ("Country", profile.country.clone().unwrap_or_default()),
+                ("Product", profile.product.clone().unwrap_or_default()),
+                ("Followers", profile.followers.to_string()),
+            ];
+            let table = Table {
+                headers: vec!["field", "value"],
+                rows: fields
+                    .iter()
+                    .map(|(field, value)| vec![field.to_string(), value.clone()])
+                    .collect(),
+            };
+            if print_report(format, &Tabulated::new(&profile, table)) {
+                return;
+            }
+
+            println!("---------------------------------------------------");
+            println!("SPOTIFY ACCOUNT");
+            println!("---------------------------------------------------");
+            for (field, value) in &fields {
+                let value = if value.is_empty() { "-" } else { value };
+                println!("{:<13} {}", format!("{}:", field), value);
+            }
+            println!();
+            match &profile.country {
+                Some(country) => println!(
+                    "[OK] Authenticated. Scans without --market check availability in {}.",
+                    country
+                ),
+                None => println!(
+                    "[OK] Authenticated. Your country is hidden, so scans without --market cannot check your market."
+                ),
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!("[ERROR] Could not fetch your profile: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_stats(config: AuditorConfig, format: OutputFormat) {
+    let mut auditor = get_auditor(config).await;
+    let progress = attach_progress_bar(&mut auditor);
+    status(format, "Collecting statistics for Liked Songs...");
+
+    let result = auditor.compute_library_stats().await;
+    progress.finish_and_clear();
+
+    match result {
+        Ok(stats) if print_report(format, &stats) => {}
+        Ok(stats) => {
+            println!();
+            println!("---------------------------------------------------");
+            println!("LIBRARY STATISTICS");
+            println!("---------------------------------------------------");
+            println!("Total Tracks:         {}", stats.total_tracks);
+            println!("Unplayable:           {}", stats.unplayable);
+            println!("Removed Globally:     {}", stats.zero_markets);
+            println!("Unique Artists:       {}", stats.unique_artists);
+            println!("Unique Albums:        {}", stats.unique_albums);
+            println!("Local Files:          {}", stats.local_files);
+            println!(
+                "Total Duration:       {}",
+                format_duration(stats.total_duration_ms)
+            );
+            println!("---------------------------------------------------");
+
+            if !stats.decades.is_empty() {
+                println!();
+                println!("{:<8} | {:>6}", "Decade", "Tracks");
+                println!("{:-<8}-+-{:->6}", "", "");
+                for (decade, count) in &stats.decades {
+                    println!("{:<8} | {:>6}", decade, count);
+                }
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Statistics failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_list(
+    config: AuditorConfig,
+    format: OutputFormat,
+    full: bool,
+    sort: Option<(PlaylistSortKey, bool)>,
+    filter: PlaylistFilter,
+    verbose: bool,
+) {
+    let auditor = get_auditor(config).await;
+    if filter.owned_only {
+        match auditor.current_user_name().await {
+            Ok(name) => status(format, &format!("Playlists owned by {}", name)),
+            Err(e) => eprintln!("[WARN] Could not fetch your profile: {}", e),
+        }
+    }
+    status(format, "Fetching your playlists...");
+
+    let filtering = filter.owned_only || filter.collaborative_only || filter.public_only;
+    let (sort_by, reverse) = sort.unwrap_or((PlaylistSortKey::Name, false));
+    let result = if sort.is_some() || filtering {
+        auditor.list_playlists_sorted(sort_by, filter).await
+    } else {
+        auditor.list_playlists().await
+    };
+    let result = result.map(|mut playlists| {
+        if reverse {
+            playlists.reverse();
+        }
+        playlists
+    });
+
+    match result {
+        Ok(playlists) if full => {
+            let mut all_details = Vec::new();
+            for pl in playlists {
+                match auditor.get_playlist_details(&pl.id).await {
+                    Ok(details) => all_details.push(details),
+                    Err(e) => {
+                        eprintln!("[WARN] Could not fetch details for {}: {}", pl.id, e);
+                    }
+                }
+            }
+            if !print_report(format, &playlist_details_report(&all_details)) {
+                for details in &all_details {
+                    print_playlist_details(details);
+                }
+                println!("---------------------------------------------------");
+            }
+        }
+        Ok(playlists) if print_report(format, &playlists) => {}
+        Ok(playlists) => {
+            // Header
+            println!();
+            print!(
+                "{:<25} | {:<30} | {:<20} | {:<6} | {:<5}",
+                "ID", "Name", "Owner", "Tracks", "Collab"
+            );
+            if verbose {
+                print!(" | Description");
+            }
+            println!();
+            print!(
+                "{:-<25}-+-{:-<30}-+-{:-<20}-+-{:-<6}-+-{:-<5}",
+                "", "", "", "", ""
+            );
+            if verbose {
+                print!("-+-{:-<60}", "");
+            }
+            println!();
+
+            for pl in playlists {
+                let id = pl.id.replace("spotify:playlist:", "");
+
+                let name = if pl.name.len() > 28 {
+                    format!("{}..", &pl.name[0..28])
+                } else {
+                    pl.name
+                };
+
+                let owner = if pl.owner_name.len() > 18 {
+                    format!("{}..", &pl.owner_name[0..18])
+                } else {
+                    pl.owner_name
+                };
+
+                let collab = if pl.is_collaborative { "Yes" } else { "No" };
+
+                print!(
+                    "{:<25} | {:<30} | {:<20} | {:<6} | {:<5}",
+                    id, name, owner, pl.total_tracks, collab
+                );
+                if verbose {
+                    print!(
+                        " | {}",
+                        truncate(pl.description.as_deref().unwrap_or("-"), 60)
+                    );
+                }
+                println!();
+            }
+            println!();
+            println!("Tip: Copy an ID and run 'audit-cli sync <ID>'");
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!("Failed to list playlists: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn playlist_details_report(all_details: &[PlaylistDetails]) -> Tabulated<'_, [PlaylistDetails]> {
+    let rows = all_details
+        .iter()
+        .map(|details| {
+            let pl = &details.summary;
+            vec![
+                pl.id.clone(),
+                pl.name.clone(),
+                pl.owner_name.clone(),
+                pl.total_tracks.to_string(),
+                pl.is_public.to_string(),
+                pl.is_collaborative.to_string(),
+                details.followers.to_string(),
+                details.snapshot_id.clone(),
+                details.summary.description.clone().unwrap_or_default(),
+                details.image_url.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    let table = Table {
+        headers: vec![
+            "id",
+            "name",
+            "owner",
+            "tracks",
+            "public",
+            "collaborative",
+            "followers",
+            "snapshot_id",
+            "description",
+            "image_url",
+        ],
+        rows,
+    };
+    Tabulated::new(all_details, table)
+}
+
+fn print_playlist_details(details: &PlaylistDetails) {
+    let pl = &details.summary;
+    println!("---------------------------------------------------");
+    println!("{}", pl.name);
+    println!("   ID:          {}", pl.id.replace("spotify:playlist:", ""));
+    println!("   Owner:       {}", pl.owner_name);
+    println!("   Tracks:      {}", pl.total_tracks);
+    println!(
+        "   Visibility:  {}{}",
+        if pl.is_public { "Public" } else { "Private" },
+        if pl.is_collaborative {
+            " (collaborative)"
+        } else {
+            ""
+        }
+    );
+    println!("   Followers:   {}", details.followers);
+    println!("   Snapshot ID: {}", details.snapshot_id);
+    if let Some(description) = &details.summary.description {
+        println!("   Description: {}", description);
+    }
+    if let Some(image_url) = &details.image_url {
+        println!("   Image:       {}", image_url);
+    }
+}
+
+async fn handle_count(config: AuditorConfig, format: OutputFormat, playlist_id: &str) {
+    let auditor = get_auditor(config).await;
+
+    match auditor.playlist_track_count(playlist_id).await {
+        Ok(tracks) => {
+            let count = PlaylistTrackCount {
+                playlist_id: playlist_id.to_string(),
+                tracks,
+            };
+            if !print_report(format, &count) {
+                println!("{}", tracks);
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Count failed: {}", e);
             process::exit(1);
         }
-    };
-    Auditor::new(spotify)
+    }
 }
 
-async fn handle_dedup() {
-    let auditor = get_auditor().await;
-    println!("Starting Deduplication of Liked Songs...");
-    println!("This will fetch your entire library to find ID conflicts. Please wait.");
+async fn handle_album_completeness(config: AuditorConfig, format: OutputFormat) {
+    let auditor = get_auditor(config).await;
+    status(
+        format,
+        "Checking which albums of your Liked Songs are incomplete...",
+    );
 
-    match auditor.deduplicate_liked_songs().await {
-        Ok(removed) => {
-            if removed.is_empty() {
-                println!();
-                println!("[OK] No safe duplicates found. Your library is clean.");
-            } else {
-                println!();
-                println!("[CLEANUP] Removed {} dead duplicate tracks:", removed.len());
-                for name in removed {
-                    println!("   - {}", name);
-                }
-                println!();
-                println!("(Note: We kept the playable versions of these tracks safe).");
+    match auditor.audit_album_completeness().await {
+        Ok(albums) => {
+            if print_report(format, &albums) {
+                return;
+            }
+            println!();
+            if albums.is_empty() {
+                println!("[OK] Every album in your Liked Songs is complete.");
+                return;
+            }
+            for album in &albums {
+                println!(
+                    "   - {} - {}: {}/{} saved, missing {}",
+                    album.artists,
+                    album.name,
+                    album.saved_tracks,
+                    album.total_tracks,
+                    album.missing_label()
+                );
             }
+            println!();
+            println!("Found {} incomplete albums.", albums.len());
         }
         Err(e) => {
+            exit_if_rate_limited(&e);
             eprintln!();
-            eprintln!("Deduplication failed: {}", e);
+            eprintln!("[ERROR] Album completeness audit failed: {}", e);
             process::exit(1);
         }
     }
 }
 
-async fn handle_scan(json_path: Option<&str>, playlist_id: Option<&str>) {
-    let auditor = get_auditor().await;
+async fn handle_followed_artists(config: AuditorConfig, format: OutputFormat) {
+    let auditor = get_auditor(config).await;
+    status(
+        format,
+        "Comparing your followed artists with your Liked Songs...",
+    );
 
-    let scan_result = if let Some(pid) = playlist_id {
-        println!("Starting scan of Playlist ID: {} ...", pid);
-        auditor.scan_playlist(pid).await
-    } else {
-        println!("Starting scan of Liked Songs...");
-        auditor.scan_liked_songs().await
-    };
+    match auditor.compare_followed_artists().await {
+        Ok(report) => {
+            let sections = [
+                ("followed_without_saves", &report.followed_without_saves),
+                ("saved_not_followed", &report.saved_not_followed),
+            ];
+            let table = Table {
+                headers: vec!["section", "artist_id", "artist", "saved_tracks"],
+                rows: sections
+                    .iter()
+                    .flat_map(|(section, artists)| {
+                        artists.iter().map(move |artist| {
+                            vec![
+                                section.to_string(),
+                                artist.id.clone(),
+                                artist.name.clone(),
+                                artist.saved_tracks.to_string(),
+                            ]
+                        })
+                    })
+                    .collect(),
+            };
+            if print_report(format, &Tabulated::new(&report, table)) {
+                return;
+            }
 
-    match scan_result {
-        Ok(summary) => {
             println!();
-            println!("---------------------------------------------------");
-            println!("AUDIT REPORT");
-            println!("---------------------------------------------------");
             println!(
-                "Target:               {}",
-                if playlist_id.is_some() {
-                    "Playlist"
-                } else {
-                    "Liked Songs"
-                }
+                "Followed artists without saved tracks ({} of {}):",
+                report.followed_without_saves.len(),
+                report.followed_total
             );
-            println!("Total Tracks Scanned: {}", summary.total_tracks_scanned);
-            println!("Problematic Tracks:   {}", summary.problematic_tracks.len());
-            println!("---------------------------------------------------");
-
-            if !summary.problematic_tracks.is_empty() {
-                println!();
-                println!("Found the following issues:");
-                for (i, track) in summary.problematic_tracks.iter().enumerate() {
-                    println!("{}. {}", i + 1, track);
-                }
-
-                println!();
-                println!("Legend:");
-                println!("  [REMOVED GLOBALLY]: Track has been removed from Spotify entirely (0 markets).");
-                println!("  [GEO-LOCKED]:       Track is available in other countries but restricted in yours.");
-            } else {
-                println!();
-                println!("No unplayable tracks found. Clean!");
+            for artist in &report.followed_without_saves {
+                println!("   - {} ({})", artist.name, artist.id);
             }
-
-            if let Some(path) = json_path {
-                match File::create(path) {
-                    Ok(mut file) => {
-                        let json_content =
-                            serde_json::to_string_pretty(&summary).unwrap_or_default();
-                        if let Err(e) = file.write_all(json_content.as_bytes()) {
-                            eprintln!();
-                            eprintln!("[ERROR] Failed to write report to file: {}", e);
-                        } else {
-                            println!();
-                            println!("[SAVED] Report saved to: {}", path);
-                        }
-                    }
-                    Err(e) => eprintln!("[ERROR] Failed to create file '{}': {}", path, e),
-                }
+            println!();
+            println!(
+                "Saved artists you do not follow ({}):",
+                report.saved_not_followed.len()
+            );
+            for artist in &report.saved_not_followed {
+                println!(
+                    "   - {} ({}): {} tracks",
+                    artist.name, artist.id, artist.saved_tracks
+                );
             }
         }
         Err(e) => {
+            exit_if_rate_limited(&e);
             eprintln!();
-            eprintln!("Audit failed: {}", e);
+            eprintln!("[ERROR] Artist comparison failed: {}", e);
             process::exit(1);
         }
     }
 }
 
-async fn handle_sync(playlist_id: &str, json_path: Option<&str>) {
-    let auditor = get_auditor().await;
+async fn handle_artist_stats(
+    config: AuditorConfig,
+    format: OutputFormat,
+    top: Option<u32>,
+    credited: bool,
+    json_path: Option<&str>,
+) {
+    let auditor = get_auditor(config).await;
+    status(format, "Counting Liked Songs per artist...");
 
-    println!("Syncing Playlist ID: {} to Liked Songs...", playlist_id);
-
-    match auditor.sync_playlist_to_liked(playlist_id).await {
-        Ok(report) => {
-            println!();
-            println!("---------------------------------------------------");
-            println!("SYNC COMPLETE");
-            println!("---------------------------------------------------");
-            println!("Initial Liked Songs:      {}", report.initial_liked_count);
-            println!(
-                "Tracks in Source Playlist:{}",
-                report.total_tracks_in_playlist
-            );
-            println!("Tracks Processed:         {}", report.tracks_processed);
-            println!("Final Liked Songs:        {}", report.final_liked_count);
-            println!("---------------------------------------------------");
-            println!("Estimated New Tracks Added: {}", report.estimated_added);
-            println!("---------------------------------------------------");
+    match auditor.list_liked_artists(!credited).await {
+        Ok(artists) => {
+            let mut ranked = artists.clone();
+            if let Some(top) = top {
+                ranked.truncate(top as usize);
+            }
+            if !print_report(format, &ranked) {
+                println!();
+                println!(
+                    "{:>4} | {:<22} | {:<40} | {:>6}",
+                    "Rank", "Artist ID", "Artist", "Tracks"
+                );
+                println!("{:->4}-+-{:-<22}-+-{:-<40}-+-{:->6}", "", "", "", "");
+                for (rank, artist) in ranked.iter().enumerate() {
+                    println!(
+                        "{:>4} | {:<22} | {:<40} | {:>6}",
+                        rank + 1,
+                        artist.id,
+                        truncate(&artist.name, 40),
+                        artist.saved_tracks
+                    );
+                }
+                println!();
+                println!("Artists: {}", artists.len());
+            }
 
             if let Some(path) = json_path {
-                match File::create(path) {
-                    Ok(mut file) => {
-                        let json_content =
-                            serde_json::to_string_pretty(&report).unwrap_or_default();
-                        if let Err(e) = file.write_all(json_content.as_bytes()) {
-                            eprintln!();
-                            eprintln!("[ERROR] Failed to write report to file: {}", e);
-                        } else {
-                            println!();
-                            println!("[SAVED] Detailed report saved to: {}", path);
-                        }
-                    }
-                    Err(e) => eprintln!("[ERROR] Failed to create file '{}': {}", path, e),
-                }
+                save_json(format, path, &artists);
             }
         }
         Err(e) => {
+            exit_if_rate_limited(&e);
             eprintln!();
-            eprintln!("[ERROR] Sync failed: {}", e);
+            eprintln!("[ERROR] Artist statistics failed: {}", e);
             process::exit(1);
         }
     }
 }
 
-async fn handle_list() {
-    let auditor = get_auditor().await;
-    println!("Fetching your playlists...");
-
-    match auditor.list_playlists().await {
-        Ok(playlists) => {
-            // Header
-            println!();
-            println!(
-                "{:<25} | {:<30} | {:<20} | {:<6} | {:<5}",
-                "ID", "Name", "Owner", "Tracks", "Collab"
-            );
-            println!(
-                "{:-<25}-+-{:-<30}-+-{:-<20}-+-{:-<6}-+-{:-<5}",
-                "", "", "", "", ""
-            );
-
-            for pl in playlists {
-                let id = pl.id.replace("spotify:playlist:", "");
-
-                let name = if pl.name.len() > 28 {
-                    format!("{}..", &pl.name[0..28])
-                } else {
-                    pl.name
-                };
+async fn handle_timeline(config: AuditorConfig, format: OutputFormat) {
+    let auditor = get_auditor(config).await;
+    status(format, "Reading when your Liked Songs were saved...");
 
-                let owner = if pl.owner_name.len() > 18 {
-                    format!("{}..", &pl.owner_name[0..18])
-                } else {
-                    pl.owner_name
-                };
+    match auditor.export_liked_timeline().await {
+        Ok(timeline) => {
+            let months = Auditor::saves_per_month(&timeline);
+            let table = Table {
+                headers: vec!["month", "saved"],
+                rows: months
+                    .iter()
+                    .map(|(month, count)| vec![month.clone(), count.to_string()])
+                    .collect(),
+            };
+            if print_report(format, &Tabulated::new(&months, table)) {
+                return;
+            }
 
-                let collab = if pl.is_collaborative { "Yes" } else { "No" };
+            println!();
+            if timeline.is_empty() {
+                println!("[OK] 'Liked Songs' is empty.");
+                return;
+            }
 
-                println!(
-                    "{:<25} | {:<30} | {:<20} | {:<6} | {:<5}",
-                    id, name, owner, pl.total_tracks, collab
-                );
+            println!("{:<8} | {:>6}", "Month", "Saved");
+            println!("{:-<8}-+-{:->6}", "", "");
+            for (month, count) in &months {
+                println!("{:<8} | {:>6}", month, count);
             }
             println!();
-            println!("Tip: Copy an ID and run 'audit-cli sync <ID>'");
+            println!("Total Saved: {}", timeline.len());
         }
         Err(e) => {
-            eprintln!("Failed to list playlists: {}", e);
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Timeline failed: {}", e);
             process::exit(1);
         }
     }
 }
 
-async fn handle_inspect(track_id: &str) {
-    let auditor = get_auditor().await;
-    println!("Inspecting Track ID: {} ...", track_id);
+async fn handle_inspect(
+    config: AuditorConfig,
+    format: OutputFormat,
+    track_id: &str,
+    market: Option<Market>,
+    with_features: bool,
+    file: Option<(OutputFormat, &str)>,
+    cache_ttl: Option<Duration>,
+) {
+    let auditor = with_track_cache(get_auditor(config).await, cache_ttl);
+    status(format, &format!("Inspecting Track ID: {} ...", track_id));
 
-    match auditor.inspect_track(track_id).await {
+    let result = if with_features {
+        auditor.inspect_track_with_features(track_id).await
+    } else {
+        auditor.inspect_track(track_id, market).await
+    };
+    if let (Ok(info), Some((file_format, path))) = (&result, file) {
+        save_report_or_exit(file_format, path, info);
+    }
+    match result {
+        Ok(info) if print_report(format, &info) => {}
         Ok(info) => {
             println!();
             println!("TRACK FORENSICS");
@@ -314,6 +3220,12 @@ async fn handle_inspect(track_id: &str) {
             println!("Popularity:    {} / 100", info.popularity);
             println!("Is Playable:   {:?}", info.is_playable);
             println!("Local File:    {}", info.is_local);
+            if let Some(original) = &info.linked_from {
+                println!(
+                    "Relinked:      This track is relinked from original ID: {}",
+                    original.trim_start_matches("spotify:track:")
+                );
+            }
             println!("---------------------------------------------------");
             println!("MARKETS ({})", info.available_markets.len());
             if info.available_markets.is_empty() {
@@ -332,6 +3244,13 @@ async fn handle_inspect(track_id: &str) {
             } else {
                 println!("   {}", info.available_markets.join(", "));
             }
+            if let (Some(code), Some(available)) = (&info.my_market, info.available_in_my_market) {
+                if available {
+                    println!("   Available in your market ({})", code);
+                } else {
+                    println!("   NOT available in your market ({})", code);
+                }
+            }
             println!("---------------------------------------------------");
             println!("EXTERNAL IDS");
             for (k, v) in &info.external_ids {
@@ -342,11 +3261,385 @@ async fn handle_inspect(track_id: &str) {
             for (k, v) in &info.external_urls {
                 println!("   {}: {}", k, v);
             }
+            if let Some(features) = &info.audio_features {
+                print_audio_features(features);
+            } else if with_features {
+                println!("---------------------------------------------------");
+                println!("AUDIO FEATURES");
+                println!("   [UNAVAILABLE] Spotify did not return audio features");
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Inspection failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn print_audio_features(features: &AudioFeatures) {
+    const KEYS: [&str; 12] = [
+        "C", "C#/Db", "D", "D#/Eb", "E", "F", "F#/Gb", "G", "G#/Ab", "A", "A#/Bb", "B",
+    ];
+    let key = usize::try_from(features.key)
+        .ok()
+        .and_then(|key| KEYS.get(key))
+        .copied()
+        .unwrap_or("?");
+    let mode = match features.mode {
+        1 => "major",
+        0 => "minor",
+        _ => "?",
+    };
+
+    println!("---------------------------------------------------");
+    println!("AUDIO FEATURES");
+    println!("   Tempo:            {:.1} BPM", features.tempo);
+    println!("   Key:              {} {}", key, mode);
+    println!("   Time Signature:   {}/4", features.time_signature);
+    println!("   Loudness:         {:.1} dB", features.loudness);
+    println!("   Energy:           {:.2}", features.energy);
+    println!("   Danceability:     {:.2}", features.danceability);
+    println!("   Valence:          {:.2}", features.valence);
+    println!("   Acousticness:     {:.2}", features.acousticness);
+    println!("   Instrumentalness: {:.2}", features.instrumentalness);
+    println!("   Liveness:         {:.2}", features.liveness);
+    println!("   Speechiness:      {:.2}", features.speechiness);
+}
+
+async fn handle_inspect_batch(
+    config: AuditorConfig,
+    format: OutputFormat,
+    track_ids: &[String],
+    json_path: Option<&str>,
+    cache_ttl: Option<Duration>,
+) {
+    let auditor = with_track_cache(get_auditor(config).await, cache_ttl);
+    status(format, &format!("Inspecting {} tracks...", track_ids.len()));
+
+    let ids: Vec<&str> = track_ids.iter().map(String::as_str).collect();
+    match auditor.inspect_batch(&ids).await {
+        Ok(inspections) => {
+            if print_report(format, &inspections) {
+                if let Some(path) = json_path {
+                    save_json(format, path, &inspections);
+                }
+                return;
+            }
+
+            println!();
+            println!(
+                "{:<22} | {:<30} | {:<25} | {:<7} | {:<12}",
+                "ID", "Name", "Artists", "Markets", "ISRC"
+            );
+            println!(
+                "{:-<22}-+-{:-<30}-+-{:-<25}-+-{:-<7}-+-{:-<12}",
+                "", "", "", "", ""
+            );
+            for info in &inspections {
+                println!(
+                    "{:<22} | {:<30} | {:<25} | {:<7} | {:<12}",
+                    info.id.trim_start_matches("spotify:track:"),
+                    truncate(&info.name, 30),
+                    truncate(&info.artists.join(", "), 25),
+                    info.available_markets.len(),
+                    info.external_ids
+                        .get("isrc")
+                        .map(String::as_str)
+                        .unwrap_or("-")
+                );
+            }
+
+            if let Some(path) = json_path {
+                save_json(format, path, &inspections);
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Inspection failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Inspects every track in `track_ids` and prints the results in `format`,
+/// defaulting to one JSON array when no other machine format was requested.
+async fn handle_inspect_many(
+    config: AuditorConfig,
+    format: OutputFormat,
+    track_ids: &[String],
+    file: Option<(OutputFormat, &str)>,
+    cache_ttl: Option<Duration>,
+) {
+    let auditor = with_track_cache(get_auditor(config).await, cache_ttl);
+
+    match auditor.inspect_tracks(track_ids).await {
+        Ok(inspections) => {
+            if let Some((file_format, path)) = file {
+                save_report_or_exit(file_format, path, &inspections);
+            }
+            let format = match format {
+                OutputFormat::Table => OutputFormat::Json,
+                other => other,
+            };
+            print_report(format, &inspections);
         }
         Err(e) => {
+            exit_if_rate_limited(&e);
             eprintln!();
             eprintln!("[ERROR] Inspection failed: {}", e);
             process::exit(1);
         }
     }
 }
+
+async fn handle_compare(
+    config: AuditorConfig,
+    format: OutputFormat,
+    playlist_a: &str,
+    playlist_b: &str,
+    json_path: Option<&str>,
+    verbose: bool,
+) {
+    let auditor = get_auditor(config).await;
+    status(
+        format,
+        &format!(
+            "Comparing playlists A: {} and B: {} ...",
+            playlist_a, playlist_b
+        ),
+    );
+
+    match auditor.compare_playlists(playlist_a, playlist_b).await {
+        Ok(diff) => {
+            if print_report(format, &diff) {
+                if let Some(path) = json_path {
+                    save_json(format, path, &diff);
+                }
+                return;
+            }
+
+            println!();
+            println!("---------------------------------------------------");
+            println!("PLAYLIST COMPARISON");
+            println!("---------------------------------------------------");
+            println!("Only in A:  {}", diff.only_in_a.len());
+            println!("Only in B:  {}", diff.only_in_b.len());
+            println!("In Both:    {}", diff.in_both.len());
+            println!("---------------------------------------------------");
+
+            if verbose {
+                print_track_section("Only in A", &diff.only_in_a);
+                print_track_section("Only in B", &diff.only_in_b);
+                print_track_section("In Both", &diff.in_both);
+            }
+
+            if let Some(path) = json_path {
+                save_json(format, path, &diff);
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Comparison failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_diff(
+    config: AuditorConfig,
+    format: OutputFormat,
+    playlist_id: &str,
+    json_path: Option<&str>,
+    verbose: bool,
+) {
+    let auditor = get_auditor(config).await;
+    status(
+        format,
+        &format!(
+            "Comparing Playlist ID: {} with your Liked Songs...",
+            playlist_id
+        ),
+    );
+
+    match auditor.diff_playlist_against_liked(playlist_id).await {
+        Ok(diff) => {
+            if print_report(format, &diff) {
+                if let Some(path) = json_path {
+                    save_json(format, path, &diff);
+                }
+                return;
+            }
+
+            println!();
+            println!("---------------------------------------------------");
+            println!("PLAYLIST VS LIKED SONGS");
+            println!("---------------------------------------------------");
+            println!(
+                "Only in Playlist (sync would add): {}",
+                diff.only_in_a.len()
+            );
+            println!(
+                "Only in Liked Songs:               {}",
+                diff.only_in_b.len()
+            );
+            println!("In Both:                           {}", diff.in_both.len());
+            println!("---------------------------------------------------");
+
+            if verbose {
+                print_track_section("Only in Playlist", &diff.only_in_a);
+                print_track_section("Only in Liked Songs", &diff.only_in_b);
+                print_track_section("In Both", &diff.in_both);
+            }
+
+            if let Some(path) = json_path {
+                save_json(format, path, &diff);
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Diff failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn handle_gap(
+    config: AuditorConfig,
+    format: OutputFormat,
+    playlist_id: &str,
+    direction: GapDirection,
+) {
+    let auditor = get_auditor(config).await;
+    status(
+        format,
+        &format!(
+            "Comparing Playlist ID: {} with your Liked Songs...",
+            playlist_id
+        ),
+    );
+
+    let result = match direction {
+        GapDirection::LikedOnly => auditor
+            .find_liked_not_in_playlist(playlist_id)
+            .await
+            .map(|liked| (None, Some(liked))),
+        GapDirection::PlaylistOnly => auditor
+            .find_playlist_not_in_liked(playlist_id)
+            .await
+            .map(|playlist| (Some(playlist), None)),
+        GapDirection::Both => auditor
+            .library_gap(playlist_id)
+            .await
+            .map(|diff| (Some(diff.only_in_a), Some(diff.only_in_b))),
+    };
+
+    match result {
+        Ok((playlist_only, liked_only)) => {
+            let mut sections: Vec<(&str, &[TrackSummary])> = Vec::new();
+            if let Some(tracks) = &liked_only {
+                sections.push(("liked_only", tracks));
+            }
+            if let Some(tracks) = &playlist_only {
+                sections.push(("playlist_only", tracks));
+            }
+            let gap: BTreeMap<&str, &[TrackSummary]> = sections.iter().copied().collect();
+            if print_report(
+                format,
+                &Tabulated::new(&gap, track_sections_table(&sections)),
+            ) {
+                return;
+            }
+
+            println!();
+            println!("---------------------------------------------------");
+            println!("LIBRARY GAP");
+            println!("---------------------------------------------------");
+            if let Some(tracks) = &liked_only {
+                println!("Liked but not in Playlist: {}", tracks.len());
+            }
+            if let Some(tracks) = &playlist_only {
+                println!("In Playlist but not Liked: {}", tracks.len());
+            }
+            println!("---------------------------------------------------");
+
+            if let Some(tracks) = &liked_only {
+                print_track_section("Liked but not in Playlist", tracks);
+            }
+            if let Some(tracks) = &playlist_only {
+                print_track_section("In Playlist but not Liked", tracks);
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Gap analysis failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn print_track_section(title: &str, tracks: &[TrackSummary]) {
+    if tracks.is_empty() {
+        return;
+    }
+    println!();
+    println!("{} ({}):", title, tracks.len());
+    for track in tracks {
+        println!("   - {}", track);
+    }
+}
+
+async fn handle_isrc_duplicates(
+    config: AuditorConfig,
+    format: OutputFormat,
+    json_path: Option<&str>,
+) {
+    let auditor = get_auditor(config).await;
+    status(format, "Collecting ISRCs from all your playlists...");
+
+    match auditor.find_isrc_duplicates_across_playlists().await {
+        Ok(clusters) => {
+            if print_report(format, &clusters) {
+                if let Some(path) = json_path {
+                    save_json(format, path, &clusters);
+                }
+                return;
+            }
+
+            println!();
+            if clusters.is_empty() {
+                println!("[OK] No song appears in several playlists under different IDs.");
+            } else {
+                println!(
+                    "[FOUND] {} songs appear in several playlists under different IDs:",
+                    clusters.len()
+                );
+                for cluster in &clusters {
+                    println!();
+                    println!("ISRC {}", cluster.isrc);
+                    for occurrence in &cluster.occurrences {
+                        println!(
+                            "   - {} | {} | {}",
+                            occurrence.playlist_name, occurrence.track_id, occurrence.track_name
+                        );
+                    }
+                }
+            }
+
+            if let Some(path) = json_path {
+                save_json(format, path, &clusters);
+            }
+        }
+        Err(e) => {
+            exit_if_rate_limited(&e);
+            eprintln!();
+            eprintln!("[ERROR] Duplicate search failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
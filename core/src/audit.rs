@@ -1,287 +1,2530 @@
+use crate::cache::TrackCache;
 use crate::models::{
-    AuditSummary, PlaylistSummary, ProblematicTrack, SyncBatchLog, SyncReport, TrackInspection,
+    AlbumCompleteness, ArtistRef, AudioFeatures, AuditDiff, AuditSummary, BackupTrack,
+    DedupCandidate, DuplicateGroup, FollowedArtistsReport, IsrcCluster, IsrcOccurrence,
+    LibraryBackup, LibraryStats, MergeReport, MergeSource, PlaylistDetails, PlaylistDiff,
+    PlaylistFilter, PlaylistSortKey, PlaylistSummary, ProblemKind, ProblematicTrack,
+    ReplacementSuggestion, SyncBatchLog, SyncDirection, SyncReport, SyncState, TrackInspection,
+    TrackSummary, UserProfile, SCHEMA_VERSION,
 };
-use futures::stream::TryStreamExt;
-use log::{debug, info};
+use crate::util::{name_similarity, parse_playlist_id, parse_playlist_url, parse_track_id};
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
 use rspotify::{
-    model::{FullTrack, Market, PlaylistId, TrackId},
+    http::HttpError,
+    model::{
+        AlbumId, FullAlbum, FullTrack, Market, Page, PlayableId, PlaylistId, PlaylistItem,
+        SavedAlbum, SavedTrack, SearchResult, SearchType, SimplifiedAlbum, SimplifiedPlaylist,
+        SimplifiedTrack, TrackId,
+    },
     prelude::*,
-    AuthCodeSpotify,
+    AuthCodeSpotify, ClientError, ClientResult,
 };
-use std::collections::HashMap;
-use std::sync::Arc;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::io::Write;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use thiserror::Error;
+use tokio::sync::OnceCell;
+use tracing::{debug, info, warn};
 
 #[derive(Error, Debug)]
 pub enum AuditError {
     #[error("Spotify API error: {0}")]
-    Spotify(#[from] rspotify::ClientError),
+    Spotify(rspotify::ClientError),
+    #[error("Rate limited by Spotify; retry after {retry_after_secs}s")]
+    RateLimit { retry_after_secs: u64 },
     #[error("Invalid Playlist ID: {0}")]
     InvalidId(String),
     #[error("Invalid Track ID: {0}")]
     InvalidTrackId(String),
+    #[error("File error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Unrecognized file format: {0}")]
+    InvalidFile(String),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("{context}: {source}")]
+    Context {
+        context: String,
+        source: Box<AuditError>,
+    },
+}
+
+/// Rate limits (429) become `AuditError::RateLimit`; every other client error is
+/// kept as is.
+impl From<ClientError> for AuditError {
+    fn from(error: ClientError) -> Self {
+        match http_status(&error) {
+            Some((429, retry_after)) => AuditError::RateLimit {
+                retry_after_secs: retry_after.unwrap_or(1),
+            },
+            _ => AuditError::Spotify(error),
+        }
+    }
+}
+
+impl AuditError {
+    /// Whether Spotify rejected the credentials (expired/revoked token or a 401).
+    pub fn is_auth_failure(&self) -> bool {
+        match self {
+            AuditError::Spotify(ClientError::InvalidToken) => true,
+            AuditError::Spotify(error) => matches!(http_status(error), Some((401, _))),
+            AuditError::Context { source, .. } => source.is_auth_failure(),
+            _ => false,
+        }
+    }
+
+    /// Whether retrying the same request cannot succeed: a malformed ID or file, or
+    /// a 4xx answer other than 401 and 429 (e.g. a deleted or private playlist).
+    pub fn is_permanent(&self) -> bool {
+        match self {
+            AuditError::InvalidId(_)
+            | AuditError::InvalidTrackId(_)
+            | AuditError::InvalidFile(_) => true,
+            AuditError::Spotify(error) => {
+                matches!(http_status(error), Some((status, _)) if (400..500).contains(&status) && status != 401)
+            }
+            AuditError::Context { source, .. } => source.is_permanent(),
+            _ => false,
+        }
+    }
+
+    /// Names the object the failed request was about, e.g. "failed on playlist X".
+    /// Rate limits are returned unchanged so callers can still match on them.
+    pub fn context(self, context: impl Into<String>) -> Self {
+        match self {
+            AuditError::RateLimit { .. } => self,
+            source => AuditError::Context {
+                context: context.into(),
+                source: Box::new(source),
+            },
+        }
+    }
+}
+
+/// Lists `ids` for an error context, e.g. "failed on tracks a, b".
+fn tracks_context(ids: &[TrackId<'_>]) -> String {
+    let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+    format!("failed on tracks {}", ids.join(", "))
+}
+
+/// Reason attached to tracks that Spotify reports as unplayable.
+const UNPLAYABLE_REASON: &str = "Track marked as unplayable by Spotify";
+
+/// Reason attached to explicit tracks by `explicit_only` scans.
+const EXPLICIT_REASON: &str = "Explicit content";
+
+/// Reason attached to tracks without playability info that no market carries anymore.
+const NO_MARKETS_REASON: &str = "Track is not available in any market";
+
+/// Consecutive authentication failures after which `Auditor::watch_playlist` gives up.
+const WATCH_MAX_AUTH_FAILURES: u32 = 3;
+
+/// Retries used by `AuditorConfig::default` for rate-limited or transient API failures.
+const DEFAULT_MAX_RETRIES: u8 = 3;
+
+/// Pause between the add batches of a sync used by `AuditorConfig::default`.
+const DEFAULT_BATCH_DELAY_MS: u64 = 100;
+
+/// Most tracks Spotify allows in one playlist.
+pub const PLAYLIST_TRACK_LIMIT: usize = 10_000;
+
+/// Page size used when walking paginated endpoints.
+const PAGE_SIZE: u32 = 50;
+
+/// Track lookup batches kept in flight by `AuditorConfig::default`.
+const DEFAULT_PARALLELISM: usize = 5;
+
+/// Stream of items from a paginated endpoint, fetched page by page with retries.
+type PageStream<'a, T> = Pin<Box<dyn Stream<Item = Result<T, AuditError>> + 'a>>;
+
+/// Calls `f` until it succeeds, retrying up to `max_retries` times when Spotify answers
+/// with a rate limit (429) or a transient server error (5xx). Rate limits wait for the
+/// `Retry-After` header when present; otherwise the delay before retry number `n`
+/// (starting at 0) is `2^n` seconds.
+pub async fn retry_with_backoff<F, Fut, T>(mut f: F, max_retries: u8) -> Result<T, AuditError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ClientResult<T>>,
+{
+    let mut attempt: u8 = 0;
+    loop {
+        let error = match f().await {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+
+        let delay = match retry_delay(&error, attempt) {
+            Some(delay) if attempt < max_retries => delay,
+            _ => return Err(error.into()),
+        };
+
+        warn!(
+            error = %error,
+            delay_secs = delay.as_secs(),
+            attempt = attempt + 1,
+            max_retries,
+            "Spotify request failed, retrying"
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// How long to wait before retrying after `error`, or `None` if it is not worth retrying.
+fn retry_delay(error: &ClientError, attempt: u8) -> Option<Duration> {
+    let (status, retry_after) = http_status(error)?;
+
+    let backoff = Duration::from_secs(1 << attempt);
+    if status == 429 {
+        Some(retry_after.map(Duration::from_secs).unwrap_or(backoff))
+    } else if (500..600).contains(&status) {
+        Some(backoff)
+    } else {
+        None
+    }
+}
+
+/// The status code and `Retry-After` seconds of `error`, when Spotify answered
+/// with an error status.
+fn http_status(error: &ClientError) -> Option<(u16, Option<u64>)> {
+    let ClientError::Http(http) = error else {
+        return None;
+    };
+    let HttpError::StatusCode(response) = http.as_ref() else {
+        return None;
+    };
+    let retry_after = response
+        .headers()
+        .get("retry-after")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    Some((response.status().as_u16(), retry_after))
+}
+
+/// Progress hook invoked as `(tracks_scanned, total_tracks)` while a scan streams items.
+/// `total_tracks` is 0 when the total could not be determined in advance.
+pub type ProgressCallback = Box<dyn Fn(u32, u32) + Send + Sync>;
+
+/// Request budget of an `Auditor`. Spotify rate limits are per app, so an app with a
+/// tighter budget can trade speed for fewer 429 answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditorConfig {
+    /// How many track lookup batches (50 tracks each) are kept in flight (at least 1).
+    pub max_concurrency: usize,
+    /// How many times a rate-limited or failed (5xx) request is retried.
+    pub max_retries: u8,
+    /// Pause before every API request (page, lookup or batch), in milliseconds.
+    pub request_delay_ms: u64,
+    /// Extra pause between the add batches of a sync, in milliseconds, so that large
+    /// playlists are not added back-to-back.
+    pub batch_delay_ms: u64,
+}
+
+impl Default for AuditorConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrency: DEFAULT_PARALLELISM,
+            max_retries: DEFAULT_MAX_RETRIES,
+            request_delay_ms: 0,
+            batch_delay_ms: DEFAULT_BATCH_DELAY_MS,
+        }
+    }
+}
+
+impl AuditorConfig {
+    fn request_delay(&self) -> Duration {
+        Duration::from_millis(self.request_delay_ms)
+    }
+
+    fn batch_delay(&self) -> Duration {
+        Duration::from_millis(self.batch_delay_ms)
+    }
+}
+
+/// Waits `delay`, if any.
+async fn pause(delay: Duration) {
+    if !delay.is_zero() {
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Awaits `request` after waiting `delay`, if any.
+async fn delayed<T>(delay: Duration, request: impl Future<Output = T>) -> T {
+    pause(delay).await;
+    request.await
 }
 
 pub struct Auditor {
     spotify: Arc<AuthCodeSpotify>,
+    progress_callback: Option<ProgressCallback>,
+    config: AuditorConfig,
+    cache: Option<Mutex<TrackCache>>,
+    sync_state: Option<PathBuf>,
+    problem_writer: Option<Mutex<Box<dyn Write + Send>>>,
+    m3u_writer: Option<Mutex<Box<dyn Write + Send>>>,
+    explicit_only: bool,
+    /// Country of the authenticated user, fetched on first use.
+    user_country: OnceCell<Option<&'static str>>,
 }
 
 impl Auditor {
+    /// An auditor with the default `AuditorConfig`.
     pub fn new(spotify: AuthCodeSpotify) -> Self {
+        Self::with_config(spotify, AuditorConfig::default())
+    }
+
+    /// An auditor whose streaming and batch operations follow `config`.
+    pub fn with_config(spotify: AuthCodeSpotify, config: AuditorConfig) -> Self {
         Self {
             spotify: Arc::new(spotify),
+            progress_callback: None,
+            config: AuditorConfig {
+                max_concurrency: config.max_concurrency.max(1),
+                ..config
+            },
+            cache: None,
+            sync_state: None,
+            problem_writer: None,
+            m3u_writer: None,
+            explicit_only: false,
+            user_country: OnceCell::new(),
+        }
+    }
+
+    /// Sets how many times a rate-limited or failed (5xx) request is retried.
+    pub fn with_max_retries(mut self, max_retries: u8) -> Self {
+        self.config.max_retries = max_retries;
+        self
+    }
+
+    /// Serves track inspections from `cache` when fresh and stores new lookups in it.
+    pub fn with_cache(mut self, cache: TrackCache) -> Self {
+        self.cache = Some(Mutex::new(cache));
+        self
+    }
+
+    /// Checkpoints `sync_playlist_to_liked` into the file at `path` after every added
+    /// batch, so that an interrupted sync can be resumed. The file is removed once a
+    /// sync finishes without failed batches.
+    pub fn with_sync_state(mut self, path: impl Into<PathBuf>) -> Self {
+        self.sync_state = Some(path.into());
+        self
+    }
+
+    async fn retry<F, Fut, T>(&self, f: F) -> Result<T, AuditError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ClientResult<T>>,
+    {
+        let delay = self.config.request_delay();
+        let mut f = f;
+        retry_with_backoff(move || delayed(delay, f()), self.config.max_retries).await
+    }
+
+    /// Walks a paginated endpoint, retrying each page request on its own so that a
+    /// transient failure does not restart (or abort) the whole listing.
+    fn paginate<'a, T, F, Fut>(&'a self, fetch: F) -> PageStream<'a, T>
+    where
+        T: 'a,
+        F: Fn(u32, u32) -> Fut + 'a,
+        Fut: Future<Output = ClientResult<Page<T>>> + 'a,
+    {
+        let max_retries = self.config.max_retries;
+        let delay = self.config.request_delay();
+        let pages = stream::try_unfold((fetch, Some(0)), move |(fetch, offset)| async move {
+            let Some(offset) = offset else {
+                return Ok(None);
+            };
+            let page = retry_with_backoff(|| delayed(delay, fetch(PAGE_SIZE, offset)), max_retries)
+                .await?;
+            let next = if page.next.is_some() && !page.items.is_empty() {
+                Some(offset + page.items.len() as u32)
+            } else {
+                None
+            };
+            Ok::<_, AuditError>(Some((page.items, (fetch, next))))
+        });
+
+        Box::pin(
+            pages
+                .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+                .try_flatten(),
+        )
+    }
+
+    fn saved_tracks(&self, market: Option<Market>) -> PageStream<'_, SavedTrack> {
+        self.paginate(move |limit, offset| {
+            self.spotify
+                .current_user_saved_tracks_manual(market, Some(limit), Some(offset))
+        })
+    }
+
+    fn saved_albums(&self) -> PageStream<'_, SavedAlbum> {
+        self.paginate(move |limit, offset| {
+            self.spotify
+                .current_user_saved_albums_manual(None, Some(limit), Some(offset))
+        })
+    }
+
+    fn album_tracks(&self, album_id: AlbumId<'static>) -> PageStream<'_, SimplifiedTrack> {
+        self.paginate(move |limit, offset| {
+            self.spotify
+                .album_track_manual(album_id.clone(), None, Some(limit), Some(offset))
+        })
+    }
+
+    fn playlist_items(
+        &self,
+        playlist_id: PlaylistId<'static>,
+        market: Option<Market>,
+    ) -> PageStream<'_, PlaylistItem> {
+        self.paginate(move |limit, offset| {
+            self.spotify.playlist_items_manual(
+                playlist_id.clone(),
+                None,
+                market,
+                Some(limit),
+                Some(offset),
+            )
+        })
+    }
+
+    /// Registers a callback that receives scan progress updates.
+    pub fn set_progress_callback(&mut self, callback: ProgressCallback) {
+        self.progress_callback = Some(callback);
+    }
+
+    fn report_progress(&self, scanned: u32, total: u32) {
+        if let Some(callback) = &self.progress_callback {
+            callback(scanned, total);
+        }
+    }
+
+    /// Streams every problematic track to `writer` as one JSON object per line
+    /// (NDJSON) the moment a scan finds it. Scans run for a market only learn where
+    /// a track is available at the end, so streamed tracks may keep the `unplayable` kind.
+    pub fn set_problem_writer(&mut self, writer: Box<dyn Write + Send>) {
+        self.problem_writer = Some(Mutex::new(writer));
+    }
+
+    /// Makes scans report the explicit tracks (reason "Explicit content") instead of
+    /// the unplayable ones. Playlist duplicates are not reported in this mode.
+    pub fn set_explicit_only(&mut self, explicit_only: bool) {
+        self.explicit_only = explicit_only;
+    }
+
+    /// Writes every track a Liked Songs or playlist scan goes through to `writer` as an
+    /// extended M3U playlist, starting with the `#EXTM3U` header written here.
+    pub fn set_m3u_writer(&mut self, mut writer: Box<dyn Write + Send>) -> Result<(), AuditError> {
+        writer.write_all(b"#EXTM3U\n")?;
+        self.m3u_writer = Some(Mutex::new(writer));
+        Ok(())
+    }
+
+    /// Writes the M3U entry of a scanned track to the M3U writer, if any.
+    fn record_scanned(&self, track: &FullTrack) -> Result<(), AuditError> {
+        if let (Some(writer), Some(entry)) = (&self.m3u_writer, m3u_entry(track)) {
+            let mut writer = writer.lock().unwrap_or_else(|e| e.into_inner());
+            writer.write_all(entry.as_bytes())?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Adds `problem` to `summary` after writing it to the problem writer, if any.
+    fn record_problem(
+        &self,
+        summary: &mut AuditSummary,
+        problem: ProblematicTrack,
+    ) -> Result<(), AuditError> {
+        if let Some(writer) = &self.problem_writer {
+            let mut writer = writer.lock().unwrap_or_else(|e| e.into_inner());
+            serde_json::to_writer(&mut *writer, &problem)?;
+            writer.write_all(b"\n")?;
+            writer.flush()?;
         }
+        summary.add_problem(problem);
+        Ok(())
     }
 
     /// Scans the user's "Liked Songs" (Saved Tracks) for unplayable items.
-    pub async fn scan_liked_songs(&self) -> Result<AuditSummary, AuditError> {
+    ///
+    /// `market` restricts playability to a given country; `None` keeps the API default.
+    /// `since` only scans the tracks saved on or after that day (UTC), for incremental scans.
+    pub async fn scan_liked_songs(
+        &self,
+        market: Option<Market>,
+        limit: Option<usize>,
+        since: Option<NaiveDate>,
+    ) -> Result<AuditSummary, AuditError> {
         let mut summary = AuditSummary::new();
-        let mut stream = self.spotify.current_user_saved_tracks(None);
+        summary.scan_window_start = since.map(|date| date.to_string());
+
+        // Only pay for the extra count request when someone is listening. How many
+        // tracks fall inside a `since` window is unknown until the scan reaches its end.
+        let total = if self.progress_callback.is_some() && since.is_none() {
+            capped_total(self.get_liked_songs_count().await?, limit)
+        } else {
+            0
+        };
+
+        let market_code = self.resolve_market_code(market).await?;
+        let mut stream = self.saved_tracks(market);
 
         while let Some(item) = stream.try_next().await? {
+            // Liked Songs come newest first, so the first older save ends the window.
+            if since.is_some_and(|since| item.added_at.date_naive() < since) {
+                break;
+            }
+            if summary.reached(limit) {
+                summary.truncated = true;
+                break;
+            }
             summary.total_tracks_scanned += 1;
-            if let Some(problem) = self.analyze_track(&item.track) {
-                summary.add_problem(problem);
+            summary.total_duration_ms += duration_ms(&item.track);
+            summary.explicit_count += u32::from(item.track.explicit);
+            self.report_progress(summary.total_tracks_scanned, total);
+            self.record_scanned(&item.track)?;
+            if let Some(problem) = self.analyze_track(&item.track, market_code) {
+                self.record_problem(&mut summary, problem)?;
+            }
+        }
+
+        if market.is_some() {
+            self.fill_available_markets(&mut summary.problematic_tracks)
+                .await?;
+        }
+        Ok(summary)
+    }
+
+    /// Scans every track of the user's saved albums for unplayable items.
+    ///
+    /// Album tracks come without a market, so availability is judged against the
+    /// market of the authenticated user.
+    pub async fn audit_saved_albums(&self) -> Result<AuditSummary, AuditError> {
+        let mut summary = AuditSummary::new();
+        let market_code = self.resolve_market_code(None).await?;
+
+        let mut albums = self.saved_albums();
+        while let Some(saved) = albums.try_next().await? {
+            let album = simplified_album(&saved.album);
+            let mut tracks = self.album_tracks(saved.album.id.clone());
+            while let Some(track) = tracks.try_next().await? {
+                summary.total_tracks_scanned += 1;
+                self.report_progress(summary.total_tracks_scanned, 0);
+                let track = full_track_from_album(track, &album);
+                summary.total_duration_ms += duration_ms(&track);
+                summary.explicit_count += u32::from(track.explicit);
+                if let Some(problem) = self.analyze_track(&track, market_code) {
+                    self.record_problem(&mut summary, problem)?;
+                }
             }
         }
 
         Ok(summary)
     }
 
-    /// Scans a specific Playlist for unplayable items.
-    pub async fn scan_playlist(&self, playlist_id_str: &str) -> Result<AuditSummary, AuditError> {
+    /// Scans a specific Playlist for unplayable items and for tracks added more than once.
+    ///
+    /// `market` defaults to the market of the authenticated user (`Market::FromToken`).
+    pub async fn scan_playlist(
+        &self,
+        playlist_id_str: &str,
+        market: Option<Market>,
+        limit: Option<usize>,
+    ) -> Result<AuditSummary, AuditError> {
         let mut summary = AuditSummary::new();
 
-        let playlist_id = PlaylistId::from_id(playlist_id_str)
-            .map_err(|_| AuditError::InvalidId(playlist_id_str.to_string()))?;
+        let playlist_id = parse_playlist_id(playlist_id_str)
+            .ok_or_else(|| AuditError::InvalidId(playlist_id_str.to_string()))?;
+
+        let total = if self.progress_callback.is_some() {
+            capped_total(
+                self.get_playlist_items_count(playlist_id.as_ref()).await?,
+                limit,
+            )
+        } else {
+            0
+        };
+
+        let market_code = self.resolve_market_code(market).await?;
+        let mut stream =
+            self.playlist_items(playlist_id, Some(market.unwrap_or(Market::FromToken)));
 
-        let mut stream = self
-            .spotify
-            .playlist_items(playlist_id, None, Some(Market::FromToken));
+        let mut occurrences: HashMap<String, u32> = HashMap::new();
+        let mut duplicates: Vec<(String, FullTrack)> = Vec::new();
 
         while let Some(item) = stream.try_next().await? {
             if let Some(rspotify::model::PlayableItem::Track(track)) = item.track {
+                if summary.reached(limit) {
+                    summary.truncated = true;
+                    break;
+                }
                 summary.total_tracks_scanned += 1;
-                if let Some(problem) = self.analyze_track(&track) {
-                    summary.add_problem(problem);
+                summary.total_duration_ms += duration_ms(&track);
+                summary.explicit_count += u32::from(track.explicit);
+                self.report_progress(summary.total_tracks_scanned, total);
+                self.record_scanned(&track)?;
+
+                let id = track.id.as_ref().map(|id| id.to_string());
+                let count = id.as_ref().map_or(1, |id| {
+                    let count = occurrences.entry(id.clone()).or_insert(0);
+                    *count += 1;
+                    *count
+                });
+                // Report each track once, however often the playlist repeats it.
+                if count == 1 {
+                    if let Some(problem) = self.analyze_track(&track, market_code) {
+                        self.record_problem(&mut summary, problem)?;
+                    }
+                }
+                // Keep one copy per duplicated ID, in playlist order.
+                if let (Some(id), 2, false) = (id, count, self.explicit_only) {
+                    duplicates.push((id, track));
                 }
             }
         }
 
+        self.fill_available_markets(&mut summary.problematic_tracks)
+            .await?;
+
+        // A duplicate that is unplayable too is already in the report.
+        let reported = summary.problematic_track_ids_set();
+        duplicates.retain(|(id, _)| !reported.contains(id.as_str()));
+
+        for (id, track) in &duplicates {
+            let reason = format!("Duplicate in playlist ({} occurrences)", occurrences[id]);
+            let mut problem = self.create_problem_report(track, &reason);
+            problem.kind = ProblemKind::Duplicate;
+            self.record_problem(&mut summary, problem)?;
+        }
+
         Ok(summary)
     }
 
-    pub async fn inspect_track(&self, track_id_str: &str) -> Result<TrackInspection, AuditError> {
-        let track_id = TrackId::from_id(track_id_str)
-            .map_err(|_| AuditError::InvalidTrackId(track_id_str.to_string()))?;
+    /// `scan_playlist` for a playlist share URL, as copied from the Spotify apps.
+    pub async fn scan_playlist_by_url(
+        &self,
+        url: &str,
+        market: Option<Market>,
+        limit: Option<usize>,
+    ) -> Result<AuditSummary, AuditError> {
+        let playlist_id = parse_playlist_url(url).ok_or_else(|| {
+            AuditError::InvalidId(format!(
+                "'{}' is not a playlist URL (expected https://open.spotify.com/playlist/<id>)",
+                url
+            ))
+        })?;
+        self.scan_playlist(playlist_id.id(), market, limit).await
+    }
 
-        let track = self.spotify.track(track_id, None).await?;
+    /// Compares two audits, matching tracks by ID.
+    ///
+    /// `fixed` holds tracks flagged in `before` but not in `after`; `regressed` holds
+    /// tracks flagged in `after` that were not flagged in `before`.
+    pub fn diff_audits(before: &AuditSummary, after: &AuditSummary) -> AuditDiff {
+        let before_ids = before.problematic_track_ids_set();
+        let after_ids = after.problematic_track_ids_set();
 
-        Ok(TrackInspection {
-            id: track.id.map(|id| id.to_string()).unwrap_or_default(),
-            name: track.name,
-            artists: track.artists.iter().map(|a| a.name.clone()).collect(),
-            album: track.album.name,
-            release_date: track.album.release_date.unwrap_or_default(),
-            duration_ms: track.duration.num_milliseconds() as u32,
-            popularity: track.popularity,
-            is_playable: track.is_playable,
-            available_markets: track
-                .available_markets
+        AuditDiff {
+            fixed: before
+                .problematic_tracks
                 .iter()
-                .map(|m| m.as_str().to_string())
+                .filter(|t| !after_ids.contains(t.id.as_str()))
+                .cloned()
                 .collect(),
-            external_ids: track.external_ids,
-            external_urls: track.external_urls,
-            disc_number: track.disc_number,
-            track_number: track.track_number,
-            is_local: track.is_local,
-        })
+            regressed: after
+                .problematic_tracks
+                .iter()
+                .filter(|t| !before_ids.contains(t.id.as_str()))
+                .cloned()
+                .collect(),
+        }
     }
 
-    pub async fn list_playlists(&self) -> Result<Vec<PlaylistSummary>, AuditError> {
-        let mut playlists = Vec::new();
-        let mut stream = self.spotify.current_user_playlists();
-
-        while let Some(pl) = stream.try_next().await? {
-            let owner_name = pl.owner.display_name.unwrap_or(pl.owner.id.to_string());
+    /// Splits two track lists into the tracks unique to each side and those in both.
+    ///
+    /// Tracks match by ID and, with `match_isrc`, also by ISRC, so the same recording
+    /// under another ID counts as present. Every section keeps the order of its source
+    /// list and lists a track once, however often it repeats.
+    pub fn diff_track_lists(
+        a: &[TrackSummary],
+        b: &[TrackSummary],
+        match_isrc: bool,
+    ) -> PlaylistDiff {
+        let keys = |tracks: &[TrackSummary]| -> (HashSet<String>, HashSet<String>) {
+            let ids = tracks.iter().map(|t| t.id.clone()).collect();
+            let isrcs = tracks.iter().filter_map(|t| t.isrc.clone()).collect();
+            (ids, isrcs)
+        };
+        let (a_ids, a_isrcs) = keys(a);
+        let (b_ids, b_isrcs) = keys(b);
+        let present = |track: &TrackSummary, ids: &HashSet<String>, isrcs: &HashSet<String>| {
+            ids.contains(&track.id)
+                || (match_isrc && track.isrc.as_ref().is_some_and(|i| isrcs.contains(i)))
+        };
 
-            playlists.push(PlaylistSummary {
-                id: pl.id.to_string(),
-                name: pl.name,
-                total_tracks: pl.tracks.total,
-                is_public: pl.public.unwrap_or(false),
-                is_collaborative: pl.collaborative,
-                owner_name,
-            });
+        let mut diff = PlaylistDiff::default();
+        let mut seen = HashSet::new();
+        for track in a {
+            if !seen.insert(track.id.as_str()) {
+                continue;
+            }
+            if present(track, &b_ids, &b_isrcs) {
+                diff.in_both.push(track.clone());
+            } else {
+                diff.only_in_a.push(track.clone());
+            }
+        }
+        let mut seen = HashSet::new();
+        for track in b {
+            if seen.insert(track.id.as_str()) && !present(track, &a_ids, &a_isrcs) {
+                diff.only_in_b.push(track.clone());
+            }
         }
+        diff
+    }
 
-        Ok(playlists)
+    /// Compares the tracks of two playlists, see `diff_track_lists`.
+    pub async fn compare_playlists(
+        &self,
+        playlist_a: &str,
+        playlist_b: &str,
+    ) -> Result<PlaylistDiff, AuditError> {
+        let a = self.playlist_track_summaries(playlist_a).await?;
+        let b = self.playlist_track_summaries(playlist_b).await?;
+        Ok(Self::diff_track_lists(&a, &b, false))
     }
 
-    async fn get_liked_songs_count(&self) -> Result<u32, AuditError> {
-        let page = self
-            .spotify
-            .current_user_saved_tracks_manual(None, Some(1), Some(0))
-            .await?;
-        Ok(page.total)
+    /// Compares a playlist (`only_in_a`) with 'Liked Songs' (`only_in_b`), matching by
+    /// ID and by ISRC. `only_in_a` holds the recordings not liked in any version; a sync
+    /// also adds playlist tracks whose recording is liked only under another ID.
+    pub async fn diff_playlist_against_liked(
+        &self,
+        playlist_id_str: &str,
+    ) -> Result<PlaylistDiff, AuditError> {
+        let playlist = self.playlist_track_summaries(playlist_id_str).await?;
+        let liked = self.liked_track_summaries().await?;
+        Ok(Self::diff_track_lists(&playlist, &liked, true))
     }
 
-    pub async fn sync_playlist_to_liked(
+    /// Compares a playlist (`only_in_a`) with 'Liked Songs' (`only_in_b`) by track ID
+    /// only, for keeping a "full library" playlist and the library in step.
+    pub async fn library_gap(&self, playlist_id_str: &str) -> Result<PlaylistDiff, AuditError> {
+        let playlist = self.playlist_track_summaries(playlist_id_str).await?;
+        let liked = self.liked_track_summaries().await?;
+        Ok(Self::diff_track_lists(&playlist, &liked, false))
+    }
+
+    /// Liked tracks missing from the playlist, in library order. See `library_gap`.
+    pub async fn find_liked_not_in_playlist(
         &self,
         playlist_id_str: &str,
-    ) -> Result<SyncReport, AuditError> {
-        let initial_liked_count = self.get_liked_songs_count().await?;
+    ) -> Result<Vec<TrackSummary>, AuditError> {
+        Ok(self.library_gap(playlist_id_str).await?.only_in_b)
+    }
 
-        let mut report = SyncReport {
-            initial_liked_count,
-            ..Default::default()
-        };
+    /// Playlist tracks that are not liked, in playlist order. See `library_gap`.
+    pub async fn find_playlist_not_in_liked(
+        &self,
+        playlist_id_str: &str,
+    ) -> Result<Vec<TrackSummary>, AuditError> {
+        Ok(self.library_gap(playlist_id_str).await?.only_in_a)
+    }
 
-        let playlist_id = PlaylistId::from_id(playlist_id_str)
-            .map_err(|_| AuditError::InvalidId(playlist_id_str.to_string()))?;
+    /// Loads the tracks of 'Liked Songs' in library order; local files are skipped.
+    async fn liked_track_summaries(&self) -> Result<Vec<TrackSummary>, AuditError> {
+        let mut liked = Vec::new();
+        let mut stream = self.saved_tracks(None);
+        while let Some(item) = stream.try_next().await? {
+            if let Some(summary) = track_summary(&item.track) {
+                liked.push(summary);
+            }
+        }
+        Ok(liked)
+    }
 
-        let mut stream = self
-            .spotify
-            .playlist_items(playlist_id, None, Some(Market::FromToken));
-        let mut track_ids: Vec<TrackId> = Vec::new();
+    /// Loads the tracks of a playlist in order; local files (no ID) are skipped.
+    async fn playlist_track_summaries(
+        &self,
+        playlist_id_str: &str,
+    ) -> Result<Vec<TrackSummary>, AuditError> {
+        let playlist_id = parse_playlist_id(playlist_id_str)
+            .ok_or_else(|| AuditError::InvalidId(playlist_id_str.to_string()))?;
 
+        let mut stream = self.playlist_items(playlist_id, None);
+        let mut tracks = Vec::new();
         while let Some(item) = stream.try_next().await? {
             if let Some(rspotify::model::PlayableItem::Track(track)) = item.track {
-                if let Some(id) = track.id {
-                    track_ids.push(id);
+                if let Some(summary) = track_summary(&track) {
+                    tracks.push(summary);
                 }
             }
-            report.total_tracks_in_playlist += 1;
         }
+        Ok(tracks)
+    }
 
-        report.tracks_processed = track_ids.len() as u32;
-
-        if track_ids.is_empty() {
-            report.final_liked_count = report.initial_liked_count;
-            return Ok(report);
-        }
+    /// Rescans a playlist (or 'Liked Songs' when `playlist_id_str` is `None`) every
+    /// `interval_secs` seconds and calls `callback` with the problems that were not
+    /// there on the previous scan. The first scan reports every problem found.
+    ///
+    /// Failed scans are logged and retried on the next tick. The watch ends with an
+    /// error right away when the failure is permanent (`AuditError::is_permanent`),
+    /// and after `WATCH_MAX_AUTH_FAILURES` authentication failures in a row.
+    pub async fn watch_playlist(
+        &self,
+        playlist_id_str: Option<&str>,
+        interval_secs: u64,
+        callback: Box<dyn Fn(AuditSummary) + Send>,
+    ) -> Result<(), AuditError> {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs.max(1)));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut previous = AuditSummary::new();
+        let mut auth_failures = 0;
 
-        for (i, chunk) in track_ids.chunks(50).enumerate() {
-            let batch_ids: Vec<String> = chunk.iter().map(|id| id.to_string()).collect();
+        loop {
+            interval.tick().await;
+            let result = match playlist_id_str {
+                Some(id) => self.scan_playlist(id, None, None).await,
+                None => self.scan_liked_songs(None, None, None).await,
+            };
 
-            match self
-                .spotify
-                .current_user_saved_tracks_add(chunk.iter().cloned())
-                .await
-            {
-                Ok(_) => {
-                    report.batch_logs.push(SyncBatchLog {
-                        batch_index: i,
-                        tracks_count: chunk.len(),
-                        track_ids: batch_ids,
-                        status: "Success".to_string(),
-                    });
+            let current = match result {
+                Ok(summary) => summary,
+                Err(e) if e.is_permanent() => return Err(e),
+                Err(e) if e.is_auth_failure() => {
+                    auth_failures += 1;
+                    if auth_failures >= WATCH_MAX_AUTH_FAILURES {
+                        return Err(e);
+                    }
+                    warn!(error = %e, attempt = auth_failures, "watch scan rejected, retrying next tick");
+                    continue;
                 }
                 Err(e) => {
-                    report.batch_logs.push(SyncBatchLog {
-                        batch_index: i,
-                        tracks_count: chunk.len(),
-                        track_ids: batch_ids,
-                        status: format!("Error: {}", e),
-                    });
+                    warn!(error = %e, "watch scan failed, retrying next tick");
+                    continue;
                 }
-            }
-        }
-
-        report.final_liked_count = self.get_liked_songs_count().await?;
+            };
+            auth_failures = 0;
 
-        if report.final_liked_count >= report.initial_liked_count {
-            report.estimated_added = report.final_liked_count - report.initial_liked_count;
+            let diff = Self::diff_audits(&previous, &current);
+            if !diff.regressed.is_empty() {
+                callback(AuditSummary {
+                    schema_version: current.schema_version.clone(),
+                    total_tracks_scanned: current.total_tracks_scanned,
+                    total_duration_ms: current.total_duration_ms,
+                    problematic_tracks: diff.regressed,
+                    scan_error: None,
+                    truncated: false,
+                    scan_window_start: None,
+                    explicit_count: current.explicit_count,
+                });
+            }
+            previous = current;
         }
-
-        Ok(report)
     }
 
-    /// Deduplicates 'Liked Songs' by removing dead tracks that share an ISRC with a living track.
-    pub async fn deduplicate_liked_songs(&self) -> Result<Vec<String>, AuditError> {
-        let mut stream = self.spotify.current_user_saved_tracks(None);
-        let mut by_isrc: HashMap<String, Vec<FullTrack>> = HashMap::new();
-
-        while let Some(item) = stream.try_next().await? {
-            let track = item.track;
-            if let Some(isrc) = track.external_ids.get("isrc") {
-                by_isrc.entry(isrc.clone()).or_default().push(track);
-            }
-        }
+    /// Scans every playlist of the current user, keyed by playlist ID.
+    ///
+    /// A playlist whose scan fails does not abort the run: its entry is an empty
+    /// summary (no counts, since `scan_playlist` returns none on error) with the error
+    /// message in `scan_error`.
+    pub async fn scan_all_playlists(&self) -> Result<HashMap<String, AuditSummary>, AuditError> {
+        let playlists = self.list_playlists().await?;
+        let mut results = HashMap::new();
 
-        let mut tracks_to_remove: Vec<TrackId> = Vec::new();
-        let mut removed_names: Vec<String> = Vec::new();
+        for playlist in playlists {
+            let id = playlist
+                .id
+                .trim_start_matches("spotify:playlist:")
+                .to_string();
+            info!(playlist = %playlist.name, id = %id, "scanning playlist");
 
-        for (isrc, tracks) in by_isrc {
-            if tracks.len() > 1 {
-                debug!("Checking ISRC {} with {} duplicates", isrc, tracks.len());
-
-                // Sort by markets count (descending), so the best one is first.
-                let mut sorted_tracks = tracks.clone();
-                sorted_tracks.sort_by_key(|t| std::cmp::Reverse(t.available_markets.len()));
-
-                let best_track = &sorted_tracks[0];
-                let best_markets = best_track.available_markets.len();
-
-                // If the best track has markets (is alive), we can safely remove the others if they have NO markets or FEWER markets.
-                // Actually, if we have duplicate ISRCs in "Liked Songs", they are redundant regardless.
-                // We should keep the "best" one and remove all others.
-
-                if best_markets > 0 {
-                    for duplicate in sorted_tracks.iter().skip(1) {
-                        let dup_markets = duplicate.available_markets.len();
-
-                        // Heuristic: Delete if it has 0 markets OR significantly fewer (e.g. strict subset logic is hard, but 0 is safe).
-                        // Or if IDs are different?
-                        if let Some(dup_id) = &duplicate.id {
-                            if Some(dup_id) != best_track.id.as_ref() {
-                                debug!("  -> Marking for removal: {} ({} markets) vs Keeper ({} markets)", duplicate.name, dup_markets, best_markets);
-                                tracks_to_remove.push(dup_id.clone());
-                                removed_names
-                                    .push(format!("{} (Markets: {})", duplicate.name, dup_markets));
-                            }
-                        }
+            let summary = match self.scan_playlist(&id, None, None).await {
+                Ok(summary) => summary,
+                Err(e) => {
+                    warn!(id = %id, error = %e, "failed to scan playlist");
+                    AuditSummary {
+                        scan_error: Some(e.to_string()),
+                        ..Default::default()
                     }
                 }
-            }
+            };
+            results.insert(id, summary);
         }
 
-        if !tracks_to_remove.is_empty() {
-            info!(
-                "Removing {} duplicate/dead tracks...",
-                tracks_to_remove.len()
-            );
-            for chunk in tracks_to_remove.chunks(50) {
-                self.spotify
-                    .current_user_saved_tracks_delete(chunk.iter().cloned())
+        Ok(results)
+    }
+
+    pub async fn inspect_track(
+        &self,
+        track_id_str: &str,
+        market: Option<Market>,
+    ) -> Result<TrackInspection, AuditError> {
+        let track_id = parse_track_id(track_id_str)
+            .ok_or_else(|| AuditError::InvalidTrackId(track_id_str.to_string()))?;
+
+        // Playability depends on the market, so only market-less lookups are cached.
+        let cached = market
+            .is_none()
+            .then(|| self.cached_track(&track_id.uri()))
+            .flatten();
+        let mut inspection = match cached {
+            Some(cached) => cached,
+            None => {
+                let track = self
+                    .retry(|| self.spotify.track(track_id.clone(), market))
                     .await?;
+                let inspection = track_inspection(track);
+                if market.is_none() {
+                    self.cache_tracks(std::slice::from_ref(&inspection));
+                }
+                inspection
             }
-        }
+        };
 
-        Ok(removed_names)
+        // The verdict is per user, so it is added after caching.
+        inspection.set_my_market(self.resolve_market_code(market).await?);
+        Ok(inspection)
     }
 
-    fn analyze_track(&self, track: &FullTrack) -> Option<ProblematicTrack> {
-        let is_playable = track.is_playable.unwrap_or(true);
+    /// Like `inspect_track` (without a market), plus the audio features of the track.
+    /// Both are requested concurrently. Spotify refuses audio features to some apps,
+    /// so a failed feature lookup only leaves `audio_features` empty.
+    pub async fn inspect_track_with_features(
+        &self,
+        track_id_str: &str,
+    ) -> Result<TrackInspection, AuditError> {
+        let track_id = parse_track_id(track_id_str)
+            .ok_or_else(|| AuditError::InvalidTrackId(track_id_str.to_string()))?;
 
-        if !is_playable {
-            return Some(
-                self.create_problem_report(track, "Track marked as unplayable by Spotify"),
-            );
+        let (track, features) = tokio::join!(
+            self.inspect_track(track_id_str, None),
+            self.retry(|| self.spotify.track_features(track_id.clone()))
+        );
+        let mut inspection = track?;
+        match features {
+            Ok(features) => inspection.audio_features = Some(audio_features(features)),
+            Err(e) => warn!(id = %track_id, error = %e, "could not fetch audio features"),
         }
-        None
+        Ok(inspection)
     }
 
-    fn create_problem_report(&self, track: &FullTrack, reason: &str) -> ProblematicTrack {
-        let artists = track
-            .artists
-            .iter()
-            .map(|a| a.name.as_str())
-            .collect::<Vec<&str>>()
-            .join(", ");
-
-        let available_markets_count = track.available_markets.len();
+    fn cached_track(&self, uri: &str) -> Option<TrackInspection> {
+        let cache = self.cache.as_ref()?.lock().ok()?;
+        cache.get(uri)
+    }
+
+    /// Stores fresh lookups in the cache, if any. Failing to persist only logs a warning.
+    fn cache_tracks(&self, tracks: &[TrackInspection]) {
+        let Some(mut cache) = self.cache.as_ref().and_then(|c| c.lock().ok()) else {
+            return;
+        };
+        for track in tracks {
+            cache.insert(track.clone());
+        }
+        if let Err(e) = cache.save() {
+            warn!(error = %e, "failed to save track cache");
+        }
+    }
+
+    /// Inspects many tracks with one `tracks()` request per 50 IDs not already cached,
+    /// keeping up to `max_concurrency` of those requests in flight.
+    ///
+    /// Every ID is validated up front, so a typo fails before any request is made.
+    pub async fn inspect_batch(
+        &self,
+        track_ids: &[&str],
+    ) -> Result<Vec<TrackInspection>, AuditError> {
+        let ids = track_ids
+            .iter()
+            .map(|raw| {
+                parse_track_id(raw).ok_or_else(|| AuditError::InvalidTrackId(raw.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut found: HashMap<String, TrackInspection> = HashMap::new();
+        let mut missing = Vec::new();
+        for id in &ids {
+            match self.cached_track(&id.uri()) {
+                Some(cached) => {
+                    found.insert(cached.id.clone(), cached);
+                }
+                None => missing.push(id.clone()),
+            }
+        }
+
+        let fetched: Vec<TrackInspection> = self
+            .lookup_tracks(&missing)
+            .await?
+            .into_iter()
+            .map(track_inspection)
+            .collect();
+        if !fetched.is_empty() {
+            self.cache_tracks(&fetched);
+        }
+        found.extend(fetched.into_iter().map(|track| (track.id.clone(), track)));
+
+        // Answer in the order the IDs were given.
+        let my_market = self.resolve_market_code(None).await?;
+        Ok(ids
+            .iter()
+            .filter_map(|id| found.get(&id.uri()).cloned())
+            .map(|mut track| {
+                track.set_my_market(my_market);
+                track
+            })
+            .collect())
+    }
+
+    /// Looks tracks up without a market, one `tracks()` request per 50 IDs with up to
+    /// `max_concurrency` of them in flight. Tracks come back in the order of `ids`.
+    async fn lookup_tracks(&self, ids: &[TrackId<'static>]) -> Result<Vec<FullTrack>, AuditError> {
+        fetch_chunks(ids, 50, self.config.max_concurrency, |chunk| async move {
+            self.retry(|| self.spotify.tracks(chunk.iter().cloned(), None))
+                .await
+        })
+        .await
+    }
+
+    /// `inspect_batch` for owned IDs, e.g. as read from a file or stdin.
+    pub async fn inspect_tracks(&self, ids: &[String]) -> Result<Vec<TrackInspection>, AuditError> {
+        let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+        self.inspect_batch(&ids).await
+    }
+
+    /// Streams 'Liked Songs' once and aggregates a `LibraryStats` overview.
+    ///
+    /// Playability is checked in the user's market; only the unplayable tracks are
+    /// looked up again without a market to tell globally removed ones apart.
+    pub async fn compute_library_stats(&self) -> Result<LibraryStats, AuditError> {
+        let mut stats = LibraryStats::default();
+        let mut artists = HashSet::new();
+        let mut albums = HashSet::new();
+        let mut unplayable_ids = Vec::new();
+
+        let total = if self.progress_callback.is_some() {
+            self.get_liked_songs_count().await?
+        } else {
+            0
+        };
+
+        let mut stream = self.saved_tracks(Some(Market::FromToken));
+        while let Some(item) = stream.try_next().await? {
+            let track = item.track;
+            stats.total_tracks += 1;
+            stats.total_duration_ms += duration_ms(&track);
+            self.report_progress(stats.total_tracks, total);
+
+            for artist in &track.artists {
+                artists.insert(
+                    artist
+                        .id
+                        .as_ref()
+                        .map_or(artist.name.clone(), |id| id.to_string()),
+                );
+            }
+            albums.insert(
+                track
+                    .album
+                    .id
+                    .as_ref()
+                    .map_or(track.album.name.clone(), |id| id.to_string()),
+            );
+            if track.is_local {
+                stats.local_files += 1;
+            }
+            if let Some(release_date) = &track.album.release_date {
+                stats.add_release_date(release_date);
+            }
+            if !track.is_playable.unwrap_or(true) {
+                stats.unplayable += 1;
+                if let Some(id) = track.id {
+                    unplayable_ids.push(id);
+                }
+            }
+        }
+
+        // Market-filtered responses omit `available_markets`.
+        stats.zero_markets = self
+            .lookup_tracks(&unplayable_ids)
+            .await?
+            .iter()
+            .filter(|track| track.available_markets.is_empty())
+            .count() as u32;
+
+        stats.unique_artists = artists.len() as u32;
+        stats.unique_albums = albums.len() as u32;
+        Ok(stats)
+    }
+
+    /// Counts 'Liked Songs' per artist, most tracks first. Every credited artist counts,
+    /// or with `primary_only` just the first-listed one. Artists are told apart by ID;
+    /// local files, which have no artist IDs, are skipped.
+    pub async fn list_liked_artists(
+        &self,
+        primary_only: bool,
+    ) -> Result<Vec<ArtistRef>, AuditError> {
+        let mut artists: HashMap<String, (String, u32)> = HashMap::new();
+        let mut stream = self.saved_tracks(None);
+
+        while let Some(item) = stream.try_next().await? {
+            let credited = if primary_only { 1 } else { usize::MAX };
+            for artist in item.track.artists.into_iter().take(credited) {
+                if let Some(id) = artist.id {
+                    artists
+                        .entry(id.id().to_string())
+                        .or_insert((artist.name, 0))
+                        .1 += 1;
+                }
+            }
+        }
+
+        Ok(rank_artists(artists))
+    }
+
+    /// Lists the artists the user follows as (artist ID, name), in API order.
+    pub async fn list_followed_artists(&self) -> Result<Vec<(String, String)>, AuditError> {
+        let mut artists = Vec::new();
+        let mut after: Option<String> = None;
+
+        loop {
+            let page = self
+                .retry(|| {
+                    self.spotify
+                        .current_user_followed_artists(after.as_deref(), Some(PAGE_SIZE))
+                })
+                .await?;
+            let done = page.next.is_none() || page.items.is_empty();
+            artists.extend(
+                page.items
+                    .into_iter()
+                    .map(|artist| (artist.id.id().to_string(), artist.name)),
+            );
+            after = page.cursors.and_then(|cursor| cursor.after);
+            if done || after.is_none() {
+                break;
+            }
+        }
+
+        Ok(artists)
+    }
+
+    /// Cross-references the followed artists with the artists of 'Liked Songs' (as
+    /// counted by `list_liked_artists`, every credited artist).
+    pub async fn compare_followed_artists(&self) -> Result<FollowedArtistsReport, AuditError> {
+        let followed = self.list_followed_artists().await?;
+        let saved = self.list_liked_artists(false).await?;
+        Ok(cross_reference_artists(followed, saved))
+    }
+
+    /// Lists the albums of which 'Liked Songs' hold only some tracks, by artist and
+    /// album name. Tracks are matched to the album by disc and track number; albums are
+    /// fetched 20 at a time, and the track list of albums over 50 tracks page by page.
+    pub async fn audit_album_completeness(&self) -> Result<Vec<AlbumCompleteness>, AuditError> {
+        let mut saved: HashMap<AlbumId<'static>, HashSet<(i32, u32)>> = HashMap::new();
+        let mut stream = self.saved_tracks(None);
+        while let Some(item) = stream.try_next().await? {
+            let track = item.track;
+            if let Some(album_id) = track.album.id {
+                saved
+                    .entry(album_id)
+                    .or_default()
+                    .insert((track.disc_number, track.track_number));
+            }
+        }
+
+        let album_ids: Vec<AlbumId<'static>> = saved.keys().cloned().collect();
+        let mut incomplete = Vec::new();
+        for chunk in album_ids.chunks(20) {
+            let albums = self
+                .retry(|| self.spotify.albums(chunk.iter().cloned(), None))
+                .await?;
+            for album in albums {
+                let positions = self.album_positions(&album).await?;
+                let Some((saved_tracks, missing_tracks)) = album_gaps(&saved, &album.id, positions)
+                else {
+                    continue;
+                };
+                if missing_tracks.is_empty() {
+                    continue;
+                }
+                incomplete.push(AlbumCompleteness {
+                    album_id: album.id.to_string(),
+                    name: album.name,
+                    artists: album
+                        .artists
+                        .iter()
+                        .map(|a| a.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    total_tracks: album.tracks.total,
+                    saved_tracks,
+                    missing_tracks,
+                });
+            }
+        }
+
+        incomplete.sort_by_key(|album| (album.artists.to_lowercase(), album.name.to_lowercase()));
+        Ok(incomplete)
+    }
+
+    /// (disc, track) numbers of every track of `album`, in album order. The album
+    /// object embeds only the first page of tracks, so longer albums are paged.
+    async fn album_positions(&self, album: &FullAlbum) -> Result<Vec<(i32, u32)>, AuditError> {
+        if album.tracks.items.len() as u32 >= album.tracks.total {
+            return Ok(album
+                .tracks
+                .items
+                .iter()
+                .map(|track| (track.disc_number, track.track_number))
+                .collect());
+        }
+        self.album_tracks(album.id.clone())
+            .map_ok(|track| (track.disc_number, track.track_number))
+            .try_collect()
+            .await
+    }
+
+    /// Lists the 'Liked Songs' whose album has no cover art. Local files, which never
+    /// have art, are skipped; in the catalogue a missing cover often marks a corrupted
+    /// or removed entry.
+    pub async fn find_tracks_without_album_art(&self) -> Result<Vec<TrackSummary>, AuditError> {
+        let mut tracks = Vec::new();
+        let mut stream = self.saved_tracks(None);
+
+        while let Some(item) = stream.try_next().await? {
+            if item.track.album.images.is_empty() {
+                tracks.extend(track_summary(&item.track));
+            }
+        }
+
+        Ok(tracks)
+    }
+
+    /// Lists the 'Liked Songs' that are in none of the playlists of your library (your
+    /// own and the ones you follow). Every playlist is streamed once; local files are
+    /// skipped.
+    pub async fn find_orphan_liked_tracks(&self) -> Result<Vec<TrackSummary>, AuditError> {
+        let mut in_playlists: HashSet<TrackId<'static>> = HashSet::new();
+        for playlist in self.list_playlists().await? {
+            let playlist_id = parse_playlist_id(&playlist.id)
+                .ok_or_else(|| AuditError::InvalidId(playlist.id.clone()))?;
+            let ids = self
+                .playlist_track_ids(playlist_id)
+                .await
+                .map_err(|e| e.context(format!("failed on playlist {}", playlist.id)))?;
+            in_playlists.extend(ids);
+        }
+
+        let mut tracks = Vec::new();
+        let mut stream = self.saved_tracks(None);
+        while let Some(item) = stream.try_next().await? {
+            if matches!(&item.track.id, Some(id) if !in_playlists.contains(id)) {
+                tracks.extend(track_summary(&item.track));
+            }
+        }
+
+        Ok(tracks)
+    }
+
+    /// Lists the 'Liked Songs' that carry no ISRC in their external IDs.
+    ///
+    /// These tracks are invisible to `deduplicate_liked_songs`, and are often old or
+    /// corrupted catalogue entries worth reporting to Spotify.
+    pub async fn find_isrc_less_tracks(&self) -> Result<Vec<TrackInspection>, AuditError> {
+        let mut tracks = Vec::new();
+        let mut stream = self.saved_tracks(None);
+
+        while let Some(item) = stream.try_next().await? {
+            if !item.track.external_ids.contains_key("isrc") {
+                tracks.push(track_inspection(item.track));
+            }
+        }
+
+        Ok(tracks)
+    }
+
+    /// Groups 'Liked Songs' that share their artists and name but not their track ID,
+    /// catching re-releases that ISRC-based dedup misses.
+    ///
+    /// Names within one artist key are grouped when `name_similarity` reaches
+    /// `similarity_threshold` (clamped to 0.0-1.0); 1.0 means exact (case-insensitive)
+    /// matches only. Nothing is removed.
+    pub async fn find_duplicate_tracks_by_name(
+        &self,
+        similarity_threshold: f32,
+    ) -> Result<Vec<DuplicateGroup>, AuditError> {
+        let mut tracks = Vec::new();
+        let mut stream = self.saved_tracks(None);
+        while let Some(item) = stream.try_next().await? {
+            tracks.push(track_inspection(item.track));
+        }
+        Ok(group_by_name(tracks, similarity_threshold))
+    }
+
+    /// Finds recordings (ISRCs) spread over two or more of your playlists under
+    /// different track IDs.
+    ///
+    /// A playlist that cannot be read is logged and skipped so one bad playlist does
+    /// not hide the clusters in the others.
+    pub async fn find_isrc_duplicates_across_playlists(
+        &self,
+    ) -> Result<Vec<IsrcCluster>, AuditError> {
+        let mut occurrences = Vec::new();
+
+        for playlist in self.list_playlists().await? {
+            let Some(playlist_id) = parse_playlist_id(&playlist.id) else {
+                continue;
+            };
+            info!(playlist = %playlist.name, "collecting ISRCs");
+
+            let mut stream = self.playlist_items(playlist_id, None);
+            loop {
+                match stream.try_next().await {
+                    Ok(Some(item)) => {
+                        if let Some(rspotify::model::PlayableItem::Track(track)) = item.track {
+                            let (Some(isrc), Some(id)) =
+                                (track.external_ids.get("isrc"), &track.id)
+                            else {
+                                continue;
+                            };
+                            occurrences.push((
+                                isrc.clone(),
+                                IsrcOccurrence {
+                                    playlist_id: playlist.id.clone(),
+                                    playlist_name: playlist.name.clone(),
+                                    track_id: id.to_string(),
+                                    track_name: track.name.clone(),
+                                },
+                            ));
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        warn!(playlist = %playlist.name, error = %e, "failed to read playlist");
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(isrc_clusters(occurrences))
+    }
+
+    pub async fn list_playlists(&self) -> Result<Vec<PlaylistSummary>, AuditError> {
+        let mut playlists = Vec::new();
+        let mut stream =
+            self.paginate(|limit, offset| self.current_user_playlists_page(limit, offset));
+
+        while let Some(ListedPlaylist {
+            playlist: pl,
+            description,
+        }) = stream.try_next().await?
+        {
+            let owner_name = pl.owner.display_name.unwrap_or(pl.owner.id.to_string());
+
+            playlists.push(PlaylistSummary {
+                id: pl.id.to_string(),
+                name: pl.name,
+                total_tracks: pl.tracks.total,
+                is_public: pl.public.unwrap_or(false),
+                is_collaborative: pl.collaborative,
+                owner_name,
+                owner_id: pl.owner.id.id().to_string(),
+                description: non_empty(description),
+            });
+        }
+
+        Ok(playlists)
+    }
+
+    /// One page of `GET /me/playlists`, read with the descriptions that
+    /// `current_user_playlists_manual` drops.
+    async fn current_user_playlists_page(
+        &self,
+        limit: u32,
+        offset: u32,
+    ) -> ClientResult<Page<ListedPlaylist>> {
+        let (limit, offset) = (limit.to_string(), offset.to_string());
+        let query = HashMap::from([("limit", limit.as_str()), ("offset", offset.as_str())]);
+        let body = self.spotify.api_get("me/playlists", &query).await?;
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// Fetches the profile of the authenticated account.
+    pub async fn get_user_profile(&self) -> Result<UserProfile, AuditError> {
+        let user = self.retry(|| self.spotify.current_user()).await?;
+        Ok(UserProfile {
+            id: user.id.id().to_string(),
+            display_name: user.display_name.filter(|name| !name.is_empty()),
+            email: user.email,
+            country: user
+                .country
+                .map(|country| <&str>::from(country).to_string()),
+            product: user
+                .product
+                .map(|product| <&str>::from(product).to_string()),
+            followers: user.followers.map_or(0, |followers| followers.total),
+        })
+    }
+
+    /// The display name of the current user, or the user ID when none is set.
+    pub async fn current_user_name(&self) -> Result<String, AuditError> {
+        let user = self.retry(|| self.spotify.me()).await?;
+        Ok(user
+            .display_name
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| user.id.id().to_string()))
+    }
+
+    /// Lists the current user's playlists, filtered and sorted in memory.
+    pub async fn list_playlists_sorted(
+        &self,
+        sort_by: PlaylistSortKey,
+        filter: PlaylistFilter,
+    ) -> Result<Vec<PlaylistSummary>, AuditError> {
+        let user_id = if filter.owned_only {
+            let user = self.retry(|| self.spotify.me()).await?;
+            user.id.id().to_string()
+        } else {
+            String::new()
+        };
+        let playlists = self.list_playlists().await?;
+        Ok(sort_and_filter_playlists(
+            playlists, sort_by, filter, &user_id,
+        ))
+    }
+
+    /// Fetches a single playlist with its description, snapshot ID, cover image and
+    /// follower count, which the playlist listing does not include.
+    pub async fn get_playlist_details(
+        &self,
+        playlist_id_str: &str,
+    ) -> Result<PlaylistDetails, AuditError> {
+        let playlist_id = parse_playlist_id(playlist_id_str)
+            .ok_or_else(|| AuditError::InvalidId(playlist_id_str.to_string()))?;
+
+        let pl = self
+            .retry(|| self.spotify.playlist(playlist_id.clone(), None, None))
+            .await?;
+        let owner_name = pl.owner.display_name.unwrap_or(pl.owner.id.to_string());
+
+        Ok(PlaylistDetails {
+            summary: PlaylistSummary {
+                id: pl.id.to_string(),
+                name: pl.name,
+                total_tracks: pl.tracks.total,
+                is_public: pl.public.unwrap_or(false),
+                is_collaborative: pl.collaborative,
+                owner_name,
+                owner_id: pl.owner.id.id().to_string(),
+                description: non_empty(pl.description),
+            },
+            snapshot_id: pl.snapshot_id,
+            image_url: pl.images.into_iter().next().map(|image| image.url),
+            followers: pl.followers.total,
+        })
+    }
+
+    /// Reads a playlist's track total without paging its items. The field filter keeps
+    /// the response to `{"tracks": {"total": N}}`, which rspotify's `FullPlaylist`
+    /// cannot deserialize, so the endpoint is called directly.
+    pub async fn playlist_track_count(&self, playlist_id_str: &str) -> Result<u32, AuditError> {
+        let playlist_id = parse_playlist_id(playlist_id_str)
+            .ok_or_else(|| AuditError::InvalidId(playlist_id_str.to_string()))?;
+        let url = format!("playlists/{}", playlist_id.id());
+        let query = HashMap::from([("fields", "tracks.total")]);
+        let body = self.retry(|| self.spotify.api_get(&url, &query)).await?;
+        let count: PlaylistTrackCount = serde_json::from_str(&body)?;
+        Ok(count.tracks.total)
+    }
+
+    async fn get_liked_songs_count(&self) -> Result<u32, AuditError> {
+        let page = self
+            .retry(|| {
+                self.spotify
+                    .current_user_saved_tracks_manual(None, Some(1), Some(0))
+            })
+            .await?;
+        Ok(page.total)
+    }
+
+    /// Reads the playlist's item total from a single-item page.
+    async fn get_playlist_items_count(
+        &self,
+        playlist_id: PlaylistId<'_>,
+    ) -> Result<u32, AuditError> {
+        let page = self
+            .retry(|| {
+                self.spotify.playlist_items_manual(
+                    playlist_id.as_ref(),
+                    None,
+                    None,
+                    Some(1),
+                    Some(0),
+                )
+            })
+            .await?;
+        Ok(page.total)
+    }
+
+    /// Adds every track of a playlist to 'Liked Songs' in batches of 50.
+    ///
+    /// Each batch is checked against the library right before it is added, so tracks
+    /// that are already liked (listed in `skipped_tracks`) or repeated within the
+    /// playlist are skipped and counted in `skipped_already_liked`; running the sync
+    /// twice is a no-op. `estimated_added` counts the tracks that were not liked yet
+    /// and whose batch was accepted (or would be, on a dry run), `added_tracks` lists
+    /// them and `confirmed_added` is the measured change in the library size. With
+    /// `dry_run` set, no write call is made. `market` defaults to the market of the
+    /// authenticated user (`Market::FromToken`) and decides which relinked track
+    /// versions are added.
+    ///
+    /// With `resume` set, the tracks of the batches recorded in the sync state file
+    /// (see `with_sync_state`) by an interrupted sync of the same playlist are not
+    /// checked or added again; those batches are reported as they were logged. They
+    /// are matched by track ID, so the sync stays complete when the playlist changed
+    /// in between.
+    pub async fn sync_playlist_to_liked(
+        &self,
+        playlist_id_str: &str,
+        market: Option<Market>,
+        dry_run: bool,
+        resume: bool,
+    ) -> Result<SyncReport, AuditError> {
+        let playlist_id = parse_playlist_id(playlist_id_str)
+            .ok_or_else(|| AuditError::InvalidId(playlist_id_str.to_string()))?;
+
+        // IDs, URIs and share URLs of the same playlist all resume the same state.
+        let mut state = match (&self.sync_state, resume) {
+            (Some(path), true) => match SyncState::load(path)? {
+                Some(state)
+                    if parse_playlist_id(&state.playlist_id).as_ref() != Some(&playlist_id) =>
+                {
+                    return Err(AuditError::InvalidFile(format!(
+                        "{}: sync state belongs to playlist {}",
+                        path.display(),
+                        state.playlist_id
+                    )));
+                }
+                Some(state) => state,
+                None => {
+                    warn!(path = %path.display(), "no sync state to resume, starting over");
+                    SyncState::default()
+                }
+            },
+            _ => SyncState::default(),
+        };
+        state.playlist_id = playlist_id.id().to_string();
+
+        let initial_liked_count = self.get_liked_songs_count().await?;
+
+        let mut report = SyncReport {
+            initial_liked_count,
+            dry_run,
+            batch_delay_ms: self.config.batch_delay_ms,
+            ..Default::default()
+        };
+
+        let mut stream =
+            self.playlist_items(playlist_id, Some(market.unwrap_or(Market::FromToken)));
+        let mut seen = HashSet::new();
+        let mut track_ids: Vec<TrackId> = Vec::new();
+
+        while let Some(item) = stream
+            .try_next()
+            .await
+            .map_err(|e| e.context(format!("failed on playlist {}", playlist_id_str)))?
+        {
+            if let Some(rspotify::model::PlayableItem::Track(track)) = item.track {
+                if let Some(id) = track.id {
+                    report.tracks_processed += 1;
+                    if seen.insert(id.clone()) {
+                        track_ids.push(id);
+                    } else {
+                        report.skipped_already_liked += 1;
+                    }
+                }
+            }
+            report.total_tracks_in_playlist += 1;
+        }
+
+        let mut resumed = HashSet::new();
+        for done in &state.completed_batches {
+            debug!(
+                batch = done.batch_index,
+                "batch already added by the interrupted sync"
+            );
+            report.estimated_added += done.tracks_count as u32;
+            report.added_tracks.extend(done.track_ids.iter().cloned());
+            report.batch_logs.push(done.clone());
+            resumed.extend(done.track_ids.iter().cloned());
+        }
+        track_ids.retain(|id| !resumed.contains(&id.to_string()));
+        // New batches are numbered after the resumed ones.
+        let first_batch = state.completed_batches.len();
+
+        for (i, chunk) in track_ids.chunks(50).enumerate() {
+            if i > 0 {
+                pause(self.config.batch_delay()).await;
+            }
+
+            let already_liked = self
+                .retry(|| {
+                    self.spotify
+                        .current_user_saved_tracks_contains(chunk.iter().cloned())
+                })
+                .await
+                .map_err(|e| e.context(tracks_context(chunk)))?;
+            let mut new_ids: Vec<TrackId> = Vec::new();
+            for (id, liked) in chunk.iter().zip(already_liked) {
+                if liked {
+                    report.skipped_tracks.push(id.to_string());
+                } else {
+                    new_ids.push(id.clone());
+                }
+            }
+            report.skipped_already_liked += (chunk.len() - new_ids.len()) as u32;
+
+            if new_ids.is_empty() {
+                continue;
+            }
+
+            let batch_ids: Vec<String> = new_ids.iter().map(|id| id.to_string()).collect();
+
+            let status = if dry_run {
+                report.estimated_added += new_ids.len() as u32;
+                "DryRun".to_string()
+            } else {
+                match self
+                    .retry(|| self.spotify.current_user_saved_tracks_add(new_ids.clone()))
+                    .await
+                {
+                    Ok(_) => {
+                        report.estimated_added += new_ids.len() as u32;
+                        report.added_tracks.extend(batch_ids.iter().cloned());
+                        "Success".to_string()
+                    }
+                    Err(e) => format!("Error: {}", e),
+                }
+            };
+
+            let log = SyncBatchLog {
+                batch_index: first_batch + i,
+                tracks_count: new_ids.len(),
+                track_ids: batch_ids,
+                status,
+            };
+            if let (Some(path), false, "Success") = (&self.sync_state, dry_run, log.status.as_str())
+            {
+                state.completed_batches.push(log.clone());
+                state.save(path)?;
+            }
+            report.batch_logs.push(log);
+        }
+
+        if dry_run {
+            report.final_liked_count = report.initial_liked_count;
+            return Ok(report);
+        }
+
+        if let Some(path) = &self.sync_state {
+            if report.batch_logs.iter().all(|b| b.status == "Success") {
+                match std::fs::remove_file(path) {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                    _ => {}
+                }
+            }
+        }
+
+        report.final_liked_count = self.get_liked_songs_count().await?;
+        report.confirmed_added = report
+            .final_liked_count
+            .saturating_sub(report.initial_liked_count);
+
+        Ok(report)
+    }
+
+    /// Adds every track of 'Liked Songs' that the playlist does not contain yet, in
+    /// library order and 100 per request. See `SyncReport` for how the report fields
+    /// map to this direction. A rejected batch is logged and the sync goes on. Tracks
+    /// that would take the playlist past `PLAYLIST_TRACK_LIMIT` are not sent; they
+    /// are listed in `failed_tracks`.
+    pub async fn sync_liked_to_playlist(
+        &self,
+        playlist_id_str: &str,
+    ) -> Result<SyncReport, AuditError> {
+        let playlist_id = parse_playlist_id(playlist_id_str)
+            .ok_or_else(|| AuditError::InvalidId(playlist_id_str.to_string()))?;
+
+        let mut report = SyncReport {
+            direction: SyncDirection::LikedToPlaylist,
+            batch_delay_ms: self.config.batch_delay_ms,
+            ..Default::default()
+        };
+
+        let mut in_playlist = HashSet::new();
+        let mut stream = self.playlist_items(playlist_id.clone(), None);
+        while let Some(item) = stream.try_next().await? {
+            report.initial_liked_count += 1;
+            if let Some(rspotify::model::PlayableItem::Track(track)) = item.track {
+                if let Some(id) = track.id {
+                    in_playlist.insert(id);
+                }
+            }
+        }
+
+        let mut new_ids: Vec<TrackId> = Vec::new();
+        let mut stream = self.saved_tracks(None);
+        while let Some(item) = stream.try_next().await? {
+            report.total_tracks_in_playlist += 1;
+            let Some(id) = item.track.id else {
+                continue; // Local files cannot be added by ID
+            };
+            report.tracks_processed += 1;
+            if in_playlist.contains(&id) {
+                report.skipped_already_liked += 1;
+                report.skipped_tracks.push(id.to_string());
+            } else {
+                new_ids.push(id);
+            }
+        }
+        // Tracks past the playlist limit would fail with a 400 halfway through.
+        let over_limit = cap_to_playlist_limit(&mut new_ids, report.initial_liked_count as usize);
+        report
+            .failed_tracks
+            .extend(over_limit.iter().map(|id| id.to_string()));
+
+        for (i, chunk) in new_ids.chunks(100).enumerate() {
+            if i > 0 {
+                pause(self.config.batch_delay()).await;
+            }
+            let batch_ids: Vec<String> = chunk.iter().map(|id| id.to_string()).collect();
+            let status = match self
+                .retry(|| {
+                    self.spotify.playlist_add_items(
+                        playlist_id.as_ref(),
+                        chunk.iter().map(|id| PlayableId::Track(id.clone())),
+                        None,
+                    )
+                })
+                .await
+            {
+                Ok(_) => {
+                    report.estimated_added += chunk.len() as u32;
+                    report.added_tracks.extend(batch_ids.iter().cloned());
+                    "Success".to_string()
+                }
+                Err(e) => {
+                    report.failed_tracks.extend(batch_ids.iter().cloned());
+                    format!("Error: {}", e)
+                }
+            };
+            report.batch_logs.push(SyncBatchLog {
+                batch_index: i,
+                tracks_count: chunk.len(),
+                track_ids: batch_ids,
+                status,
+            });
+        }
+
+        report.final_liked_count = self.get_playlist_items_count(playlist_id.as_ref()).await?;
+        report.confirmed_added = report
+            .final_liked_count
+            .saturating_sub(report.initial_liked_count);
+        info!(
+            added = report.added_tracks.len(),
+            playlist = %playlist_id_str,
+            "synced liked songs to playlist"
+        );
+
+        Ok(report)
+    }
+
+    /// Exports every track in 'Liked Songs', in library order, for backup purposes.
+    pub async fn export_liked_songs(&self) -> Result<Vec<BackupTrack>, AuditError> {
+        let mut tracks = Vec::new();
+        let mut stream = self.saved_tracks(None);
+
+        while let Some(item) = stream.try_next().await? {
+            let track = item.track;
+            let Some(id) = track.id else {
+                continue; // Local files cannot be re-added by ID
+            };
+
+            tracks.push(BackupTrack {
+                id: id.to_string(),
+                name: track.name,
+                artists: track.artists.into_iter().map(|a| a.name).collect(),
+                album: track.album.name,
+                isrc: track.external_ids.get("isrc").cloned(),
+                added_at: item.added_at.to_rfc3339(),
+                available_markets: track
+                    .available_markets
+                    .iter()
+                    .map(|m| m.as_str().to_string())
+                    .collect(),
+                is_playable: track.is_playable,
+            });
+        }
+
+        Ok(tracks)
+    }
+
+    /// Lists when each liked track was saved, oldest first, as (added at, ID, name).
+    /// Local files are skipped.
+    pub async fn export_liked_timeline(
+        &self,
+    ) -> Result<Vec<(DateTime<Utc>, TrackId<'static>, String)>, AuditError> {
+        let mut timeline = Vec::new();
+        let mut stream = self.saved_tracks(None);
+
+        while let Some(item) = stream.try_next().await? {
+            if let Some(id) = item.track.id {
+                timeline.push((item.added_at, id, item.track.name));
+            }
+        }
+
+        timeline.sort_by_key(|(added_at, _, _)| *added_at);
+        Ok(timeline)
+    }
+
+    /// Counts the saves of a timeline per month, keyed "YYYY-MM" (so in order).
+    pub fn saves_per_month(
+        timeline: &[(DateTime<Utc>, TrackId<'static>, String)],
+    ) -> BTreeMap<String, u32> {
+        let mut months = BTreeMap::new();
+        for (added_at, _, _) in timeline {
+            *months
+                .entry(added_at.format("%Y-%m").to_string())
+                .or_insert(0) += 1;
+        }
+        months
+    }
+
+    /// Copies every track in 'Liked Songs', in library order, into a new private
+    /// playlist called `name` and returns the ID of that playlist. Local files are
+    /// skipped, and a library larger than `PLAYLIST_TRACK_LIMIT` is cut off at the limit.
+    pub async fn liked_to_new_playlist(&self, name: &str) -> Result<String, AuditError> {
+        let mut ids = Vec::new();
+        let mut stream = self.saved_tracks(None);
+        while let Some(item) = stream.try_next().await? {
+            if let Some(id) = item.track.id {
+                ids.push(id);
+            }
+        }
+
+        let playlist = self.create_playlist_with_tracks(name, false, ids).await?;
+        info!(playlist = %playlist, "copied liked songs to new playlist");
+        Ok(playlist.to_string())
+    }
+
+    /// Copies the tracks of `source_id`, in playlist order, into a new playlist called
+    /// `new_name` and returns the ID of that playlist. Local files and episodes are
+    /// skipped, and a source longer than `PLAYLIST_TRACK_LIMIT` is cut off at the limit.
+    pub async fn clone_playlist(
+        &self,
+        source_id: &str,
+        new_name: &str,
+        public: bool,
+    ) -> Result<String, AuditError> {
+        let source =
+            parse_playlist_id(source_id).ok_or_else(|| AuditError::InvalidId(source_id.into()))?;
+
+        let ids = self.playlist_track_ids(source.clone()).await?;
+        let playlist = self
+            .create_playlist_with_tracks(new_name, public, ids)
+            .await?;
+        info!(source = %source, playlist = %playlist, "cloned playlist");
+        Ok(playlist.to_string())
+    }
+
+    /// Creates a playlist called `name` holding `ids` in order, added 100 at a time,
+    /// and returns its ID. Tracks past `PLAYLIST_TRACK_LIMIT` are dropped (with a
+    /// warning) before the playlist is created.
+    async fn create_playlist_with_tracks(
+        &self,
+        name: &str,
+        public: bool,
+        mut ids: Vec<TrackId<'static>>,
+    ) -> Result<PlaylistId<'static>, AuditError> {
+        cap_to_playlist_limit(&mut ids, 0);
+
+        let user = self.retry(|| self.spotify.me()).await?;
+        let playlist = self
+            .retry(|| {
+                self.spotify
+                    .user_playlist_create(user.id.as_ref(), name, Some(public), None, None)
+            })
+            .await?;
+
+        for chunk in ids.chunks(100) {
+            debug!(count = chunk.len(), playlist = %playlist.id, "adding tracks to playlist");
+            self.retry(|| {
+                self.spotify.playlist_add_items(
+                    playlist.id.as_ref(),
+                    chunk.iter().map(|id| PlayableId::Track(id.clone())),
+                    None,
+                )
+            })
+            .await?;
+        }
+        info!(count = ids.len(), playlist = %playlist.id, "filled new playlist");
+        Ok(playlist.id)
+    }
+
+    /// Appends the tracks of every playlist in `source_ids`, in order, to `dest_id`. With
+    /// `dedup`, tracks already in the destination (or added from an earlier source) are
+    /// skipped by ID. Local files and episodes are always skipped.
+    pub async fn merge_playlists(
+        &self,
+        source_ids: &[&str],
+        dest_id: &str,
+        dedup: bool,
+    ) -> Result<MergeReport, AuditError> {
+        let dest =
+            parse_playlist_id(dest_id).ok_or_else(|| AuditError::InvalidId(dest_id.into()))?;
+        let sources = source_ids
+            .iter()
+            .map(|raw| parse_playlist_id(raw).ok_or_else(|| AuditError::InvalidId(raw.to_string())))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut seen: HashSet<TrackId<'static>> = if dedup {
+            self.playlist_track_ids(dest.clone())
+                .await?
+                .into_iter()
+                .collect()
+        } else {
+            HashSet::new()
+        };
+
+        let mut report = MergeReport {
+            destination_id: dest.id().to_string(),
+            dedup,
+            ..Default::default()
+        };
+        for source in sources {
+            let mut entry = MergeSource {
+                playlist_id: source.id().to_string(),
+                ..Default::default()
+            };
+            let mut ids = self.playlist_track_ids(source).await?;
+            if dedup {
+                let before = ids.len();
+                ids.retain(|id| seen.insert(id.clone()));
+                entry.skipped = (before - ids.len()) as u32;
+            }
+
+            for chunk in ids.chunks(100) {
+                debug!(
+                    count = chunk.len(),
+                    source = %entry.playlist_id,
+                    playlist = %dest,
+                    "adding merged tracks to playlist"
+                );
+                self.retry(|| {
+                    self.spotify.playlist_add_items(
+                        dest.as_ref(),
+                        chunk.iter().map(|id| PlayableId::Track(id.clone())),
+                        None,
+                    )
+                })
+                .await?;
+                entry.added += chunk.len() as u32;
+            }
+
+            report.total_added += entry.added;
+            report.total_skipped += entry.skipped;
+            report.sources.push(entry);
+        }
+
+        info!(
+            added = report.total_added,
+            skipped = report.total_skipped,
+            playlist = %dest,
+            "merged playlists"
+        );
+        Ok(report)
+    }
+
+    /// IDs of the tracks in a playlist, in playlist order. Local files and episodes,
+    /// which have no track ID, are left out.
+    async fn playlist_track_ids(
+        &self,
+        playlist_id: PlaylistId<'static>,
+    ) -> Result<Vec<TrackId<'static>>, AuditError> {
+        let mut ids = Vec::new();
+        let mut stream = self.playlist_items(playlist_id, None);
+        while let Some(item) = stream.try_next().await? {
+            if let Some(rspotify::model::PlayableItem::Track(track)) = item.track {
+                if let Some(id) = track.id {
+                    ids.push(id);
+                }
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Writes a backup of 'Liked Songs' to `path` and returns the number of tracks saved.
+    ///
+    /// The backup records when it was taken and for which user. It is written to
+    /// `<path>.tmp` first and then renamed, so an interrupted run never leaves a
+    /// truncated file in place of a previous backup.
+    pub async fn backup_liked_songs(&self, path: &str) -> Result<u32, AuditError> {
+        let user = self.retry(|| self.spotify.me()).await?;
+        let tracks = self.export_liked_songs().await?;
+        let count = tracks.len() as u32;
+
+        let backup = LibraryBackup {
+            saved_at: Utc::now().to_rfc3339(),
+            user_id: user.id.id().to_string(),
+            tracks,
+        };
+
+        let tmp_path = format!("{}.tmp", path);
+        std::fs::write(&tmp_path, serde_json::to_string_pretty(&backup)?)?;
+        std::fs::rename(&tmp_path, path)?;
+
+        Ok(count)
+    }
+
+    /// Compares a `backup_liked_songs` file with the catalogue today and returns the
+    /// backed-up tracks that were playable then (or not recorded either way) but are
+    /// unplayable in the user's market now. Tracks Spotify no longer returns are skipped.
+    pub async fn get_recently_unavailable(
+        &self,
+        backup_path: &str,
+    ) -> Result<Vec<ProblematicTrack>, AuditError> {
+        let content = std::fs::read_to_string(backup_path)?;
+        let backup: LibraryBackup = serde_json::from_str(&content)
+            .map_err(|e| AuditError::InvalidFile(format!("{}: {}", backup_path, e)))?;
+
+        let ids: Vec<&str> = backup.tracks.iter().map(|t| t.id.as_str()).collect();
+        let current = self.inspect_batch(&ids).await?;
+        let market_code = self.resolve_market_code(None).await?;
+        Ok(newly_unavailable(&backup.tracks, current, market_code))
+    }
+
+    /// Re-likes the tracks listed in a local file: a `LibraryBackup`, a scan report
+    /// (`AuditSummary`) or any JSON array of objects with an `id` field.
+    ///
+    /// Batches of 50 are checked with `current_user_saved_tracks_contains` so tracks
+    /// that are already liked are skipped. With `dry_run` set, nothing is added.
+    pub async fn restore_liked_from_file(
+        &self,
+        path: &str,
+        dry_run: bool,
+    ) -> Result<SyncReport, AuditError> {
+        let content = std::fs::read_to_string(path)?;
+        let track_ids = parse_restore_ids(&content)
+            .map_err(|e| AuditError::InvalidFile(format!("{}: {}", path, e)))?;
+        self.restore_liked_songs(&track_ids, dry_run).await
+    }
+
+    /// Re-adds the given tracks to 'Liked Songs' in batches of 50.
+    ///
+    /// IDs that cannot be parsed, or whose batch the API fails to check or add, are
+    /// listed in `failed_tracks` instead of aborting the restore.
+    pub async fn restore_liked_songs(
+        &self,
+        track_ids: &[String],
+        dry_run: bool,
+    ) -> Result<SyncReport, AuditError> {
+        let initial_liked_count = self.get_liked_songs_count().await?;
+
+        let mut report = SyncReport {
+            initial_liked_count,
+            total_tracks_in_playlist: track_ids.len() as u32,
+            dry_run,
+            ..Default::default()
+        };
+
+        let mut valid_ids: Vec<TrackId> = Vec::new();
+        for raw in track_ids {
+            match parse_track_id(raw) {
+                Some(id) => valid_ids.push(id),
+                None => {
+                    warn!(id = %raw, "skipping unresolvable track ID");
+                    report.failed_tracks.push(raw.clone());
+                }
+            }
+        }
+        report.tracks_processed = valid_ids.len() as u32;
+
+        for (i, chunk) in valid_ids.chunks(50).enumerate() {
+            let already_liked = match self
+                .retry(|| {
+                    self.spotify
+                        .current_user_saved_tracks_contains(chunk.iter().cloned())
+                })
+                .await
+            {
+                Ok(liked) => liked,
+                Err(e) => {
+                    let batch_ids: Vec<String> = chunk.iter().map(|id| id.to_string()).collect();
+                    report.failed_tracks.extend(batch_ids.iter().cloned());
+                    report.batch_logs.push(SyncBatchLog {
+                        batch_index: i,
+                        tracks_count: chunk.len(),
+                        track_ids: batch_ids,
+                        status: format!("Error: {}", e),
+                    });
+                    continue;
+                }
+            };
+            let mut new_ids: Vec<TrackId> = Vec::new();
+            for (id, liked) in chunk.iter().zip(already_liked) {
+                if liked {
+                    report.skipped_tracks.push(id.to_string());
+                } else {
+                    new_ids.push(id.clone());
+                }
+            }
+            report.skipped_already_liked += (chunk.len() - new_ids.len()) as u32;
+
+            if new_ids.is_empty() {
+                continue;
+            }
+
+            let batch_ids: Vec<String> = new_ids.iter().map(|id| id.to_string()).collect();
+
+            let status = if dry_run {
+                report.estimated_added += new_ids.len() as u32;
+                "DryRun".to_string()
+            } else {
+                match self
+                    .retry(|| self.spotify.current_user_saved_tracks_add(new_ids.clone()))
+                    .await
+                {
+                    Ok(_) => {
+                        report.estimated_added += new_ids.len() as u32;
+                        report.added_tracks.extend(batch_ids.iter().cloned());
+                        "Success".to_string()
+                    }
+                    Err(e) => {
+                        report.failed_tracks.extend(batch_ids.iter().cloned());
+                        format!("Error: {}", e)
+                    }
+                }
+            };
+
+            report.batch_logs.push(SyncBatchLog {
+                batch_index: i,
+                tracks_count: new_ids.len(),
+                track_ids: batch_ids,
+                status,
+            });
+        }
+
+        if dry_run {
+            report.final_liked_count = report.initial_liked_count;
+            return Ok(report);
+        }
+
+        report.final_liked_count = self.get_liked_songs_count().await?;
+        report.confirmed_added = report
+            .final_liked_count
+            .saturating_sub(report.initial_liked_count);
+
+        Ok(report)
+    }
+
+    /// Removes unplayable tracks from a playlist and returns what was removed.
+    ///
+    /// With `only_globally_removed`, geo-locked tracks (available in at least one
+    /// market) are left alone. Duplicate entries flagged by the scan are never removed.
+    /// With `dry_run`, the same tracks are selected and returned but nothing is deleted.
+    pub async fn remove_unplayable_from_playlist(
+        &self,
+        playlist_id_str: &str,
+        only_globally_removed: bool,
+        dry_run: bool,
+    ) -> Result<Vec<ProblematicTrack>, AuditError> {
+        let playlist_id = parse_playlist_id(playlist_id_str)
+            .ok_or_else(|| AuditError::InvalidId(playlist_id_str.to_string()))?;
+
+        // The scan already looked up where each unplayable track is still available.
+        let summary = self.scan_playlist(playlist_id_str, None, None).await?;
+
+        let mut candidates: Vec<(TrackId, ProblematicTrack)> = summary
+            .problematic_tracks
+            .into_iter()
+            .filter(|problem| problem.kind != ProblemKind::Duplicate)
+            .filter_map(|problem| {
+                let id = parse_track_id(&problem.id)?;
+                Some((id, problem))
+            })
+            .collect();
+
+        if only_globally_removed {
+            candidates.retain(|(_, problem)| problem.kind == ProblemKind::GloballyRemoved);
+        }
+
+        if dry_run {
+            info!(
+                count = candidates.len(),
+                playlist = %playlist_id,
+                "dry run: unplayable tracks would be removed from playlist"
+            );
+            return Ok(candidates.into_iter().map(|(_, problem)| problem).collect());
+        }
+
+        for chunk in candidates.chunks(100) {
+            debug!(
+                count = chunk.len(),
+                playlist = %playlist_id,
+                "removing tracks from playlist"
+            );
+            self.retry(|| {
+                self.spotify.playlist_remove_all_occurrences_of_items(
+                    playlist_id.as_ref(),
+                    chunk.iter().map(|(id, _)| PlayableId::Track(id.clone())),
+                    None,
+                )
+            })
+            .await?;
+        }
+
+        Ok(candidates.into_iter().map(|(_, problem)| problem).collect())
+    }
+
+    /// Searches the catalog for a release of the recording `isrc` that is available in
+    /// at least one market. Returns the first such search result.
+    pub async fn find_replacement_by_isrc(
+        &self,
+        isrc: &str,
+    ) -> Result<Option<TrackInspection>, AuditError> {
+        let query = format!("isrc:{}", isrc);
+        let result = self
+            .retry(|| {
+                self.spotify
+                    .search(&query, SearchType::Track, None, None, Some(PAGE_SIZE), None)
+            })
+            .await?;
+
+        let SearchResult::Tracks(page) = result else {
+            return Ok(None);
+        };
+        Ok(page
+            .items
+            .into_iter()
+            .find(|track| !track.available_markets.is_empty())
+            .map(track_inspection))
+    }
+
+    /// Looks up the ISRC of `track_id_str` and searches for a playable release of the
+    /// same recording. Returns `None` when the track has no ISRC or nothing was found.
+    pub async fn find_replacement_for_track(
+        &self,
+        track_id_str: &str,
+    ) -> Result<Option<TrackInspection>, AuditError> {
+        let track = self.inspect_track(track_id_str, None).await?;
+        match track.external_ids.get("isrc") {
+            Some(isrc) => self.find_replacement_by_isrc(isrc).await,
+            None => Ok(None),
+        }
+    }
+
+    /// For every recording (ISRC) in 'Liked Songs' whose liked versions are all removed
+    /// globally, looks for a playable replacement with `find_replacement_by_isrc`.
+    /// Recordings that still have a live liked version are left to `deduplicate_liked_songs`.
+    pub async fn suggest_isrc_replacements(
+        &self,
+    ) -> Result<Vec<ReplacementSuggestion>, AuditError> {
+        let mut stream = self.saved_tracks(None);
+        let mut by_isrc: BTreeMap<String, Vec<FullTrack>> = BTreeMap::new();
+
+        while let Some(item) = stream.try_next().await? {
+            let track = item.track;
+            if let Some(isrc) = track.external_ids.get("isrc") {
+                by_isrc.entry(isrc.clone()).or_default().push(track);
+            }
+        }
+
+        let mut suggestions = Vec::new();
+        for (isrc, tracks) in by_isrc {
+            if tracks
+                .iter()
+                .any(|t| t.is_local || !t.available_markets.is_empty())
+            {
+                continue;
+            }
+            let replacement = self.find_replacement_by_isrc(&isrc).await?;
+            for track in &tracks {
+                if let Some(dead) = track_summary(track) {
+                    suggestions.push(ReplacementSuggestion {
+                        dead,
+                        replacement: replacement.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(suggestions)
+    }
+
+    /// Removes every occurrence of a single track from a playlist.
+    pub async fn remove_playlist_track(
+        &self,
+        playlist_id_str: &str,
+        track_id_str: &str,
+    ) -> Result<(), AuditError> {
+        let playlist_id = parse_playlist_id(playlist_id_str)
+            .ok_or_else(|| AuditError::InvalidId(playlist_id_str.to_string()))?;
+        let track_id = parse_track_id(track_id_str)
+            .ok_or_else(|| AuditError::InvalidTrackId(track_id_str.to_string()))?;
+        self.retry(|| {
+            self.spotify.playlist_remove_all_occurrences_of_items(
+                playlist_id.as_ref(),
+                [PlayableId::Track(track_id.clone())],
+                None,
+            )
+        })
+        .await?;
+        info!(track = %track_id_str, playlist = %playlist_id_str, "removed track from playlist");
+        Ok(())
+    }
+
+    /// Removes a single track from 'Liked Songs'.
+    pub async fn remove_liked_track(&self, track_id_str: &str) -> Result<(), AuditError> {
+        let track_id = parse_track_id(track_id_str)
+            .ok_or_else(|| AuditError::InvalidTrackId(track_id_str.to_string()))?;
+        self.retry(|| {
+            self.spotify
+                .current_user_saved_tracks_delete([track_id.clone()])
+        })
+        .await?;
+        info!(track = %track_id_str, "removed track from liked songs");
+        Ok(())
+    }
+
+    /// Removes the given tracks from 'Liked Songs' in batches of 50 and returns how many
+    /// were removed. Every ID is validated before anything is deleted.
+    pub async fn unsave_tracks(&self, track_ids: &[&str]) -> Result<u32, AuditError> {
+        let ids = track_ids
+            .iter()
+            .map(|id| parse_track_id(id).ok_or_else(|| AuditError::InvalidTrackId(id.to_string())))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for chunk in ids.chunks(50) {
+            self.retry(|| {
+                self.spotify
+                    .current_user_saved_tracks_delete(chunk.iter().cloned())
+            })
+            .await
+            .map_err(|e| e.context(tracks_context(chunk)))?;
+        }
+        info!(count = ids.len(), "removed tracks from liked songs");
+        Ok(ids.len() as u32)
+    }
+
+    /// Deduplicates 'Liked Songs' by removing dead tracks that share an ISRC with a living track.
+    ///
+    /// With `dry_run` set, the same selection is made but nothing is deleted; the returned
+    /// names are the tracks that would be removed. Runs `plan_liked_dedup` and then
+    /// `execute_liked_dedup` without asking; split the two to confirm in between.
+    pub async fn deduplicate_liked_songs(&self, dry_run: bool) -> Result<Vec<String>, AuditError> {
+        let plan = self.plan_liked_dedup().await?;
+        if dry_run {
+            info!(
+                count = plan.len(),
+                "dry run: duplicate/dead tracks would be removed"
+            );
+        } else {
+            self.execute_liked_dedup(&plan).await?;
+        }
+        Ok(plan.into_iter().map(|candidate| candidate.label).collect())
+    }
+
+    /// Picks the dead liked tracks that share an ISRC with a living liked track. Deletes nothing.
+    pub async fn plan_liked_dedup(&self) -> Result<Vec<DedupCandidate>, AuditError> {
+        let mut stream = self.saved_tracks(None);
+        let mut by_isrc: HashMap<String, Vec<FullTrack>> = HashMap::new();
+
+        while let Some(item) = stream.try_next().await? {
+            let track = item.track;
+            if let Some(isrc) = track.external_ids.get("isrc") {
+                by_isrc.entry(isrc.clone()).or_default().push(track);
+            }
+        }
+
+        Ok(dedup_candidates(by_isrc))
+    }
+
+    /// Removes the `approved` candidates of a `plan_liked_dedup` from 'Liked Songs'.
+    pub async fn execute_liked_dedup(&self, approved: &[DedupCandidate]) -> Result<(), AuditError> {
+        let tracks_to_remove = candidate_ids(approved)?;
+        if !tracks_to_remove.is_empty() {
+            info!(
+                count = tracks_to_remove.len(),
+                "removing duplicate/dead tracks"
+            );
+        }
+        for chunk in tracks_to_remove.chunks(50) {
+            self.retry(|| {
+                self.spotify
+                    .current_user_saved_tracks_delete(chunk.iter().cloned())
+            })
+            .await
+            .map_err(|e| e.context(tracks_context(chunk)))?;
+        }
+        Ok(())
+    }
+
+    /// Deduplicates a playlist by removing tracks that share an ISRC with a better-available
+    /// track in the same playlist. Every occurrence of a removed track is dropped.
+    ///
+    /// Follows the same keeper rules and `dry_run` semantics as `deduplicate_liked_songs`.
+    pub async fn deduplicate_playlist(
+        &self,
+        playlist_id_str: &str,
+        dry_run: bool,
+    ) -> Result<Vec<String>, AuditError> {
+        let plan = self.plan_playlist_dedup(playlist_id_str).await?;
+        if dry_run {
+            info!(
+                count = plan.len(),
+                playlist = %playlist_id_str,
+                "dry run: duplicate tracks would be removed from playlist"
+            );
+        } else {
+            self.execute_playlist_dedup(playlist_id_str, &plan).await?;
+        }
+        Ok(plan.into_iter().map(|candidate| candidate.label).collect())
+    }
+
+    /// Picks the playlist tracks that share an ISRC with a better-available track in the
+    /// same playlist. Removes nothing.
+    pub async fn plan_playlist_dedup(
+        &self,
+        playlist_id_str: &str,
+    ) -> Result<Vec<DedupCandidate>, AuditError> {
+        let playlist_id = parse_playlist_id(playlist_id_str)
+            .ok_or_else(|| AuditError::InvalidId(playlist_id_str.to_string()))?;
+
+        // No market, so `available_markets` is populated for the keeper choice.
+        let mut stream = self.playlist_items(playlist_id, None);
+        let mut by_isrc: HashMap<String, Vec<FullTrack>> = HashMap::new();
+
+        while let Some(item) = stream.try_next().await? {
+            if let Some(rspotify::model::PlayableItem::Track(track)) = item.track {
+                if let Some(isrc) = track.external_ids.get("isrc") {
+                    by_isrc.entry(isrc.clone()).or_default().push(track);
+                }
+            }
+        }
+
+        Ok(dedup_candidates(by_isrc))
+    }
+
+    /// Removes every occurrence of the `approved` candidates of a `plan_playlist_dedup`
+    /// from the playlist.
+    pub async fn execute_playlist_dedup(
+        &self,
+        playlist_id_str: &str,
+        approved: &[DedupCandidate],
+    ) -> Result<(), AuditError> {
+        let playlist_id = parse_playlist_id(playlist_id_str)
+            .ok_or_else(|| AuditError::InvalidId(playlist_id_str.to_string()))?;
+        let tracks_to_remove = candidate_ids(approved)?;
+
+        for chunk in tracks_to_remove.chunks(100) {
+            debug!(
+                count = chunk.len(),
+                playlist = %playlist_id,
+                "removing duplicate tracks from playlist"
+            );
+            self.retry(|| {
+                self.spotify.playlist_remove_all_occurrences_of_items(
+                    playlist_id.as_ref(),
+                    chunk.iter().map(|id| PlayableId::Track(id.clone())),
+                    None,
+                )
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Looks the problems without a market list up again without a market and
+    /// reclassifies them. Market-filtered responses omit `available_markets`, which
+    /// would otherwise make every unplayable track look removed.
+    async fn fill_available_markets(
+        &self,
+        problems: &mut [ProblematicTrack],
+    ) -> Result<(), AuditError> {
+        let ids: Vec<TrackId<'static>> = problems
+            .iter()
+            .filter(|problem| problem.kind == ProblemKind::Unplayable)
+            .filter(|problem| problem.available_markets.is_empty())
+            .filter_map(|problem| parse_track_id(&problem.id))
+            .collect();
+
+        let mut markets_by_id: HashMap<String, Vec<String>> = HashMap::new();
+        for track in self.lookup_tracks(&ids).await? {
+            if let Some(id) = track.id {
+                markets_by_id.insert(id.to_string(), track.available_markets);
+            }
+        }
+        for problem in problems.iter_mut() {
+            if let Some(markets) = markets_by_id.remove(&problem.id) {
+                problem.set_available_markets(markets);
+            }
+        }
+        Ok(())
+    }
+
+    /// ISO code of the market a scan runs against: the requested country, or the
+    /// country of the authenticated user for `Market::FromToken` and `None`.
+    /// `None` when the account does not expose its country. The user's country is
+    /// requested once per auditor.
+    async fn resolve_market_code(
+        &self,
+        market: Option<Market>,
+    ) -> Result<Option<&'static str>, AuditError> {
+        match market {
+            Some(Market::Country(country)) => Ok(Some(country.into())),
+            Some(Market::FromToken) | None => self
+                .user_country
+                .get_or_try_init(|| async {
+                    let user = self.retry(|| self.spotify.me()).await?;
+                    Ok(user.country.map(<&'static str>::from))
+                })
+                .await
+                .copied(),
+        }
+    }
+
+    fn analyze_track(
+        &self,
+        track: &FullTrack,
+        market_code: Option<&str>,
+    ) -> Option<ProblematicTrack> {
+        if self.explicit_only {
+            let mut problem = self.create_problem_report(track, explicit_problem(track)?);
+            problem.kind = ProblemKind::Explicit;
+            return Some(problem);
+        }
+        let reason = availability_problem(
+            track.is_playable,
+            track.is_local,
+            &track.available_markets,
+            market_code,
+        )?;
+        Some(self.create_problem_report(track, &reason))
+    }
+
+    fn create_problem_report(&self, track: &FullTrack, reason: &str) -> ProblematicTrack {
+        let artist_names: Vec<String> = track.artists.iter().map(|a| a.name.clone()).collect();
+
+        let available_markets_count = track.available_markets.len();
 
         ProblematicTrack {
             id: track
@@ -290,15 +2533,1400 @@ impl Auditor {
                 .map(|id| id.to_string())
                 .unwrap_or_else(|| "unknown".to_string()),
             name: track.name.clone(),
-            artists,
+            artists: artist_names.join(", "),
+            artist_names,
             album: track.album.name.clone(),
             reason: reason.to_string(),
+            kind: unplayable_kind(track),
+            external_url: track
+                .external_urls
+                .get("spotify")
+                .cloned()
+                .unwrap_or_default(),
+            available_markets_count,
+            available_markets: track.available_markets.clone(),
+            duration_ms: duration_ms(track),
+        }
+    }
+}
+
+/// Why a track cannot be played, or `None` when it can.
+///
+/// Spotify only sets `is_playable` when the request carried a market. Without it,
+/// the track is judged by `available_markets`: no market at all means it was
+/// removed, and a list without `market_code` means it is locked out of that market.
+fn availability_problem(
+    is_playable: Option<bool>,
+    is_local: bool,
+    available_markets: &[String],
+    market_code: Option<&str>,
+) -> Option<String> {
+    match is_playable {
+        Some(false) => Some(UNPLAYABLE_REASON.to_string()),
+        Some(true) => None,
+        None if is_local => None,
+        None if available_markets.is_empty() => Some(NO_MARKETS_REASON.to_string()),
+        None => market_code
+            .filter(|code| !available_markets.iter().any(|m| m == code))
+            .map(|code| format!("Track is not available in market {}", code)),
+    }
+}
+
+/// Runs `fetch` on every `chunk_size` slice of `items` with up to `concurrency`
+/// calls in flight. Results come back in chunk order; the first error is returned.
+async fn fetch_chunks<'a, T, R, F, Fut>(
+    items: &'a [T],
+    chunk_size: usize,
+    concurrency: usize,
+    fetch: F,
+) -> Result<Vec<R>, AuditError>
+where
+    F: FnMut(&'a [T]) -> Fut,
+    Fut: Future<Output = Result<Vec<R>, AuditError>>,
+{
+    let batches: Vec<Vec<R>> = stream::iter(items.chunks(chunk_size))
+        .map(fetch)
+        .buffered(concurrency.max(1))
+        .try_collect()
+        .await?;
+    Ok(batches.into_iter().flatten().collect())
+}
+
+/// Kind of an unplayable `track`. Market-filtered responses (`is_playable` set) carry
+/// no market list, so those tracks stay `Unplayable` until `fill_available_markets`.
+fn unplayable_kind(track: &FullTrack) -> ProblemKind {
+    if track.is_playable.is_some() && !track.is_local && track.available_markets.is_empty() {
+        ProblemKind::Unplayable
+    } else {
+        ProblemKind::from_availability(track.is_local, track.available_markets.len())
+    }
+}
+
+/// Why an `explicit_only` scan reports `track`, or `None` when it is not explicit.
+fn explicit_problem(track: &FullTrack) -> Option<&'static str> {
+    track.explicit.then_some(EXPLICIT_REASON)
+}
+
+/// The album fields `analyze_track` reads, taken from a saved album.
+fn simplified_album(album: &FullAlbum) -> SimplifiedAlbum {
+    SimplifiedAlbum {
+        artists: album.artists.clone(),
+        external_urls: album.external_urls.clone(),
+        id: Some(album.id.clone()),
+        images: album.images.clone(),
+        name: album.name.clone(),
+        release_date: Some(album.release_date.clone()),
+        ..Default::default()
+    }
+}
+
+/// Fills a `FullTrack` from an album track so it can go through `analyze_track`.
+/// Album tracks carry no popularity or external IDs; those stay empty.
+fn full_track_from_album(track: SimplifiedTrack, album: &SimplifiedAlbum) -> FullTrack {
+    FullTrack {
+        album: album.clone(),
+        artists: track.artists,
+        available_markets: track.available_markets.unwrap_or_default(),
+        disc_number: track.disc_number,
+        duration: track.duration,
+        explicit: track.explicit,
+        external_ids: HashMap::new(),
+        external_urls: track.external_urls,
+        href: track.href,
+        id: track.id,
+        is_local: track.is_local,
+        is_playable: track.is_playable,
+        linked_from: track.linked_from,
+        restrictions: track.restrictions,
+        name: track.name,
+        popularity: 0,
+        preview_url: track.preview_url,
+        track_number: track.track_number,
+    }
+}
+
+fn audio_features(features: rspotify::model::AudioFeatures) -> AudioFeatures {
+    AudioFeatures {
+        tempo: features.tempo,
+        key: features.key,
+        mode: features.mode as i32,
+        time_signature: features.time_signature,
+        energy: features.energy,
+        danceability: features.danceability,
+        valence: features.valence,
+        acousticness: features.acousticness,
+        instrumentalness: features.instrumentalness,
+        liveness: features.liveness,
+        speechiness: features.speechiness,
+        loudness: features.loudness,
+    }
+}
+
+/// The progress total of a scan stopped after `limit` tracks.
+fn capped_total(total: u32, limit: Option<usize>) -> u32 {
+    limit.map_or(total, |limit| {
+        total.min(limit.try_into().unwrap_or(u32::MAX))
+    })
+}
+
+fn duration_ms(track: &FullTrack) -> u64 {
+    track.duration.num_milliseconds().max(0) as u64
+}
+
+/// The `#EXTINF` line and URI of `track` in an extended M3U playlist: duration in
+/// seconds (-1 when unknown), "Artist - Title", then the Spotify URL, or the track URI
+/// when there is none. Local files have neither and get no entry.
+fn m3u_entry(track: &FullTrack) -> Option<String> {
+    let uri = track
+        .external_urls
+        .get("spotify")
+        .cloned()
+        .or_else(|| track.id.as_ref().map(|id| id.uri()))?;
+    let seconds = match duration_ms(track) {
+        0 => -1,
+        ms => ms.div_ceil(1000) as i64,
+    };
+    let artists: Vec<&str> = track.artists.iter().map(|a| a.name.as_str()).collect();
+    let title = if artists.is_empty() {
+        track.name.clone()
+    } else {
+        format!("{} - {}", artists.join(", "), track.name)
+    };
+    Some(format!("#EXTINF:{},{}\n{}\n", seconds, title, uri))
+}
+
+fn track_summary(track: &FullTrack) -> Option<TrackSummary> {
+    Some(TrackSummary {
+        id: track.id.as_ref()?.to_string(),
+        name: track.name.clone(),
+        artists: track.artists.iter().map(|a| a.name.clone()).collect(),
+        isrc: track.external_ids.get("isrc").cloned(),
+    })
+}
+
+/// Drops the tracks of `ids` that would take a playlist already holding `in_playlist`
+/// items past `PLAYLIST_TRACK_LIMIT` (with a warning) and returns them.
+fn cap_to_playlist_limit<T>(ids: &mut Vec<T>, in_playlist: usize) -> Vec<T> {
+    let room = PLAYLIST_TRACK_LIMIT.saturating_sub(in_playlist);
+    if ids.len() <= room {
+        return Vec::new();
+    }
+    warn!(
+        count = ids.len(),
+        in_playlist,
+        limit = PLAYLIST_TRACK_LIMIT,
+        "too many tracks for one playlist, keeping only the first ones"
+    );
+    ids.split_off(room)
+}
+
+/// How many tracks of `album_id` are saved and which of its `positions` are not.
+/// `None` (with a warning) when no saved track is on `album_id`, e.g. because
+/// Spotify answered with the canonical album of a legacy or relinked ID.
+fn album_gaps(
+    saved: &HashMap<AlbumId<'static>, HashSet<(i32, u32)>>,
+    album_id: &AlbumId<'static>,
+    positions: Vec<(i32, u32)>,
+) -> Option<(u32, Vec<(i32, u32)>)> {
+    let Some(have) = saved.get(album_id) else {
+        warn!(album = %album_id, "album lookup returned an album that was not requested");
+        return None;
+    };
+    let missing = positions
+        .into_iter()
+        .filter(|position| !have.contains(position))
+        .collect();
+    Some((have.len() as u32, missing))
+}
+
+/// Splits followed and saved artists (as ranked by `rank_artists`) into the followed
+/// ones without saved tracks and the saved ones that are not followed.
+fn cross_reference_artists(
+    followed: Vec<(String, String)>,
+    saved: Vec<ArtistRef>,
+) -> FollowedArtistsReport {
+    let followed_ids: HashSet<&str> = followed.iter().map(|(id, _)| id.as_str()).collect();
+    let saved_ids: HashSet<&str> = saved.iter().map(|artist| artist.id.as_str()).collect();
+
+    let mut followed_without_saves: Vec<ArtistRef> = followed
+        .iter()
+        .filter(|(id, _)| !saved_ids.contains(id.as_str()))
+        .map(|(id, name)| ArtistRef {
+            id: id.clone(),
+            name: name.clone(),
+            saved_tracks: 0,
+        })
+        .collect();
+    followed_without_saves.sort_by_key(|artist| artist.name.to_lowercase());
+
+    let saved_not_followed = saved
+        .into_iter()
+        .filter(|artist| !followed_ids.contains(artist.id.as_str()))
+        .collect();
+
+    FollowedArtistsReport {
+        followed_total: followed.len() as u32,
+        followed_without_saves,
+        saved_not_followed,
+    }
+}
+
+/// Flattens artist ID -> (name, tracks) into a list, most tracks first, then by name.
+fn rank_artists(artists: HashMap<String, (String, u32)>) -> Vec<ArtistRef> {
+    let mut ranked: Vec<ArtistRef> = artists
+        .into_iter()
+        .map(|(id, (name, saved_tracks))| ArtistRef {
+            id,
+            name,
+            saved_tracks,
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.saved_tracks
+            .cmp(&a.saved_tracks)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    ranked
+}
+
+fn track_inspection(track: FullTrack) -> TrackInspection {
+    TrackInspection {
+        schema_version: SCHEMA_VERSION.to_string(),
+        id: track.id.map(|id| id.to_string()).unwrap_or_default(),
+        name: track.name,
+        artists: track.artists.iter().map(|a| a.name.clone()).collect(),
+        album: track.album.name,
+        release_date: track.album.release_date.unwrap_or_default(),
+        duration_ms: track.duration.num_milliseconds() as u32,
+        popularity: track.popularity,
+        is_playable: track.is_playable,
+        available_markets: track
+            .available_markets
+            .iter()
+            .map(|m| m.as_str().to_string())
+            .collect(),
+        external_ids: track.external_ids.into_iter().collect(),
+        external_urls: track.external_urls.into_iter().collect(),
+        disc_number: track.disc_number,
+        track_number: track.track_number,
+        is_local: track.is_local,
+        linked_from: track.linked_from.map(|link| link.uri),
+        audio_features: None,
+        my_market: None,
+        available_in_my_market: None,
+    }
+}
+
+/// Clusters tracks by lowercase artist list, then by name similarity within each artist.
+/// Only clusters holding at least two distinct track IDs are returned.
+fn group_by_name(tracks: Vec<TrackInspection>, similarity_threshold: f32) -> Vec<DuplicateGroup> {
+    let threshold = similarity_threshold.clamp(0.0, 1.0);
+    let mut by_artists: BTreeMap<String, Vec<DuplicateGroup>> = BTreeMap::new();
+
+    for track in tracks {
+        let artists = track.artists.join(", ").to_lowercase();
+        let name = track.name.trim().to_lowercase();
+        let groups = by_artists.entry(artists.clone()).or_default();
+
+        let matched = groups.iter_mut().find(|group| {
+            if threshold >= 1.0 {
+                group.name == name
+            } else {
+                name_similarity(&group.name, &name) >= threshold
+            }
+        });
+        match matched {
+            Some(group) => {
+                if group.tracks.iter().all(|t| t.id != track.id) {
+                    group.tracks.push(track);
+                }
+            }
+            None => groups.push(DuplicateGroup {
+                artists,
+                name,
+                tracks: vec![track],
+            }),
+        }
+    }
+
+    by_artists
+        .into_values()
+        .flatten()
+        .filter(|group| group.tracks.len() > 1)
+        .collect()
+}
+
+/// Groups `(isrc, occurrence)` pairs by ISRC and keeps the groups that span at least
+/// two playlists and at least two distinct track IDs, sorted by ISRC.
+fn isrc_clusters(occurrences: Vec<(String, IsrcOccurrence)>) -> Vec<IsrcCluster> {
+    let mut by_isrc: BTreeMap<String, Vec<IsrcOccurrence>> = BTreeMap::new();
+    for (isrc, occurrence) in occurrences {
+        by_isrc.entry(isrc).or_default().push(occurrence);
+    }
+
+    by_isrc
+        .into_iter()
+        .filter(|(_, occurrences)| {
+            let playlists: HashSet<&str> =
+                occurrences.iter().map(|o| o.playlist_id.as_str()).collect();
+            let tracks: HashSet<&str> = occurrences.iter().map(|o| o.track_id.as_str()).collect();
+            playlists.len() > 1 && tracks.len() > 1
+        })
+        .map(|(isrc, occurrences)| IsrcCluster { isrc, occurrences })
+        .collect()
+}
+
+/// Applies `filter` (`user_id` decides ownership) and sorts by `sort_by`. Ties keep
+/// the API order.
+fn sort_and_filter_playlists(
+    mut playlists: Vec<PlaylistSummary>,
+    sort_by: PlaylistSortKey,
+    filter: PlaylistFilter,
+    user_id: &str,
+) -> Vec<PlaylistSummary> {
+    playlists.retain(|pl| {
+        (!filter.owned_only || pl.owner_id == user_id)
+            && (!filter.collaborative_only || pl.is_collaborative)
+            && (!filter.public_only || pl.is_public)
+    });
+    playlists.sort_by(|a, b| a.cmp_by(b, sort_by));
+    playlists
+}
+
+/// Splits a group of tracks sharing an ISRC into the one available in the most markets
+/// and the remaining duplicates. `tracks` must not be empty.
+fn pick_best_by_isrc(mut tracks: Vec<FullTrack>) -> (FullTrack, Vec<FullTrack>) {
+    tracks.sort_by_key(|t| std::cmp::Reverse(t.available_markets.len()));
+    let best = tracks.remove(0);
+    (best, tracks)
+}
+
+/// `removable_duplicates` as a dedup plan.
+fn dedup_candidates(by_isrc: HashMap<String, Vec<FullTrack>>) -> Vec<DedupCandidate> {
+    removable_duplicates(by_isrc)
+        .into_iter()
+        .map(|(id, label)| DedupCandidate {
+            id: id.to_string(),
+            label,
+        })
+        .collect()
+}
+
+/// Parses the track IDs of dedup candidates; any malformed ID rejects the whole list.
+fn candidate_ids(candidates: &[DedupCandidate]) -> Result<Vec<TrackId<'static>>, AuditError> {
+    candidates
+        .iter()
+        .map(|c| parse_track_id(&c.id).ok_or_else(|| AuditError::InvalidTrackId(c.id.clone())))
+        .collect()
+}
+
+/// Picks, for every ISRC group, the duplicates that can go without losing availability.
+/// Returns each removable track ID once, with a display label for the report.
+fn removable_duplicates(
+    by_isrc: HashMap<String, Vec<FullTrack>>,
+) -> Vec<(TrackId<'static>, String)> {
+    let mut removable = Vec::new();
+    let mut seen = HashSet::new();
+
+    for (isrc, tracks) in by_isrc {
+        if tracks.len() < 2 {
+            continue;
+        }
+        debug!(isrc = %isrc, count = tracks.len(), "checking duplicates");
+
+        let (best_track, duplicates) = pick_best_by_isrc(tracks);
+        let best_markets = best_track.available_markets.len();
+        if best_markets == 0 {
+            continue;
+        }
+
+        for duplicate in duplicates {
+            let dup_markets = duplicate.available_markets.len();
+
+            if !is_redundant_duplicate(&best_track, &duplicate) {
+                debug!(
+                    track = %duplicate.name,
+                    markets = dup_markets,
+                    "keeping duplicate: playable where the keeper is not"
+                );
+                continue;
+            }
+
+            if let Some(dup_id) = duplicate.id {
+                if Some(&dup_id) != best_track.id.as_ref() && seen.insert(dup_id.clone()) {
+                    debug!(
+                        track = %duplicate.name,
+                        markets = dup_markets,
+                        keeper_markets = best_markets,
+                        "marking duplicate for removal"
+                    );
+                    removable.push((
+                        dup_id,
+                        format!("{} (Markets: {})", duplicate.name, dup_markets),
+                    ));
+                }
+            }
+        }
+    }
+
+    removable
+}
+
+/// A duplicate is only redundant when every market it is available in is also covered
+/// by the keeper, so removing it can never make the recording unplayable somewhere.
+fn is_redundant_duplicate(keeper: &FullTrack, duplicate: &FullTrack) -> bool {
+    let keeper_markets: HashSet<&str> = keeper
+        .available_markets
+        .iter()
+        .map(String::as_str)
+        .collect();
+    duplicate
+        .available_markets
+        .iter()
+        .all(|market| keeper_markets.contains(market.as_str()))
+}
+
+/// A `GET /me/playlists` item: rspotify's `SimplifiedPlaylist` has no `description`.
+#[derive(Deserialize)]
+struct ListedPlaylist {
+    #[serde(flatten)]
+    playlist: SimplifiedPlaylist,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+/// A `GET /playlists/{id}?fields=tracks.total` response.
+#[derive(Deserialize)]
+struct PlaylistTrackCount {
+    tracks: PlaylistTrackTotal,
+}
+
+#[derive(Deserialize)]
+struct PlaylistTrackTotal {
+    total: u32,
+}
+
+/// The API returns an empty string rather than null for no description.
+fn non_empty(description: Option<String>) -> Option<String> {
+    description.filter(|d| !d.is_empty())
+}
+
+/// The tracks of `current` that were playable when `backup` was taken but that are
+/// unplayable now, either by `is_playable` or by the market check of `inspect_batch`.
+///
+/// Backups taken without a market carry no `is_playable`; those tracks count as
+/// playable back then when their `available_markets` included `market_code` (any
+/// market when the country is unknown).
+fn newly_unavailable(
+    backup: &[BackupTrack],
+    current: Vec<TrackInspection>,
+    market_code: Option<&str>,
+) -> Vec<ProblematicTrack> {
+    let was_playable: HashMap<&str, bool> = backup
+        .iter()
+        .map(|track| {
+            let playable = track.is_playable.unwrap_or_else(|| {
+                availability_problem(None, false, &track.available_markets, market_code).is_none()
+            });
+            (track.id.as_str(), playable)
+        })
+        .collect();
+
+    current
+        .into_iter()
+        .filter(|track| was_playable.get(track.id.as_str()) == Some(&true))
+        .filter(|track| track.is_playable.or(track.available_in_my_market) == Some(false))
+        .map(|track| ProblematicTrack {
+            kind: ProblemKind::from_availability(track.is_local, track.available_markets.len()),
+            reason: "Playable at backup time, unplayable now".to_string(),
+            artists: track.artists.join(", "),
+            artist_names: track.artists,
             external_url: track
                 .external_urls
                 .get("spotify")
                 .cloned()
                 .unwrap_or_default(),
-            available_markets_count,
+            available_markets_count: track.available_markets.len(),
+            duration_ms: u64::from(track.duration_ms),
+            id: track.id,
+            name: track.name,
+            album: track.album,
+            available_markets: track.available_markets,
+        })
+        .collect()
+}
+
+/// File layouts accepted by `restore_liked_from_file`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RestoreSource {
+    Backup(LibraryBackup),
+    Summary(AuditSummary),
+    Tracks(Vec<TrackRef>),
+}
+
+/// Minimal view of any exported track record (backup entry, `TrackInspection`, ...).
+#[derive(Deserialize)]
+struct TrackRef {
+    id: String,
+}
+
+fn parse_restore_ids(content: &str) -> Result<Vec<String>, serde_json::Error> {
+    Ok(match serde_json::from_str(content)? {
+        RestoreSource::Backup(backup) => backup.tracks.into_iter().map(|t| t.id).collect(),
+        RestoreSource::Summary(summary) => summary
+            .problematic_tracks
+            .into_iter()
+            .map(|t| t.id)
+            .collect(),
+        RestoreSource::Tracks(tracks) => tracks.into_iter().map(|t| t.id).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{inspection, problem};
+
+    fn summary(ids: &[&str]) -> AuditSummary {
+        AuditSummary {
+            total_tracks_scanned: 10,
+            problematic_tracks: ids.iter().map(|id| problem(id, 0)).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn track_with_markets(id: &str, markets: &[&str]) -> FullTrack {
+        serde_json::from_value(serde_json::json!({
+            "album": {
+                "album_type": "album",
+                "artists": [],
+                "available_markets": [],
+                "external_urls": {},
+                "href": null,
+                "id": null,
+                "images": [],
+                "name": "Album"
+            },
+            "artists": [],
+            "available_markets": markets,
+            "disc_number": 1,
+            "duration_ms": 180000,
+            "explicit": false,
+            "external_ids": { "isrc": "USABC0000001" },
+            "external_urls": {},
+            "href": null,
+            "id": id,
+            "is_local": false,
+            "name": "Track",
+            "popularity": 0,
+            "preview_url": null,
+            "track_number": 1
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_dedup_removes_subset_duplicate() {
+        let keeper = track_with_markets("4iV5W9uYEdYUVa79Axb7Rh", &["US", "MX", "DE"]);
+        let dead = track_with_markets("1301WleyT98MSxVHPZCA6M", &[]);
+        let subset = track_with_markets("2TpxZ7JUBn3uw46aR7qd6V", &["US", "MX"]);
+        let equal = track_with_markets("3n3Ppam7vgaVa1iaRUc9Lp", &["DE", "MX", "US"]);
+
+        assert!(is_redundant_duplicate(&keeper, &dead));
+        assert!(is_redundant_duplicate(&keeper, &subset));
+        assert!(is_redundant_duplicate(&keeper, &equal));
+    }
+
+    #[test]
+    fn test_dedup_keeps_superset_and_disjoint_duplicates() {
+        let keeper = track_with_markets("4iV5W9uYEdYUVa79Axb7Rh", &["US", "MX"]);
+        let superset = track_with_markets("1301WleyT98MSxVHPZCA6M", &["US", "MX", "JP"]);
+        let disjoint = track_with_markets("2TpxZ7JUBn3uw46aR7qd6V", &["JP"]);
+        let overlapping = track_with_markets("3n3Ppam7vgaVa1iaRUc9Lp", &["US", "JP"]);
+
+        assert!(!is_redundant_duplicate(&keeper, &superset));
+        assert!(!is_redundant_duplicate(&keeper, &disjoint));
+        assert!(!is_redundant_duplicate(&keeper, &overlapping));
+    }
+
+    #[test]
+    fn test_pick_best_by_isrc_keeps_widest_availability() {
+        let dead = track_with_markets("1301WleyT98MSxVHPZCA6M", &[]);
+        let wide = track_with_markets("4iV5W9uYEdYUVa79Axb7Rh", &["US", "MX", "DE"]);
+        let narrow = track_with_markets("2TpxZ7JUBn3uw46aR7qd6V", &["US"]);
+
+        let (best, rest) = pick_best_by_isrc(vec![dead, wide, narrow]);
+
+        assert_eq!(best.id.unwrap().id(), "4iV5W9uYEdYUVa79Axb7Rh");
+        assert_eq!(rest.len(), 2);
+    }
+
+    #[test]
+    fn test_removable_duplicates_skips_keeper_and_repeats() {
+        let keeper = track_with_markets("4iV5W9uYEdYUVa79Axb7Rh", &["US", "MX"]);
+        let dead = track_with_markets("1301WleyT98MSxVHPZCA6M", &[]);
+        let mut by_isrc = HashMap::new();
+        // A playlist can hold the same track more than once.
+        by_isrc.insert(
+            "USABC0000001".to_string(),
+            vec![keeper.clone(), keeper, dead.clone(), dead],
+        );
+
+        let removable = removable_duplicates(by_isrc);
+
+        assert_eq!(removable.len(), 1);
+        assert_eq!(removable[0].0.id(), "1301WleyT98MSxVHPZCA6M");
+    }
+
+    #[test]
+    fn test_dedup_plan_ids_roundtrip() {
+        let keeper = track_with_markets("4iV5W9uYEdYUVa79Axb7Rh", &["US"]);
+        let dead = track_with_markets("1301WleyT98MSxVHPZCA6M", &[]);
+        let by_isrc = HashMap::from([("USABC0000001".to_string(), vec![keeper, dead])]);
+
+        let plan = dedup_candidates(by_isrc);
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].id, "spotify:track:1301WleyT98MSxVHPZCA6M");
+        let ids = candidate_ids(&plan).unwrap();
+        assert_eq!(ids[0].id(), "1301WleyT98MSxVHPZCA6M");
+
+        let bad = DedupCandidate {
+            id: "not a track".to_string(),
+            label: String::new(),
+        };
+        assert!(candidate_ids(&[bad]).is_err());
+    }
+
+    fn summaries(ids: &[&str]) -> Vec<TrackSummary> {
+        ids.iter()
+            .map(|id| TrackSummary {
+                id: id.to_string(),
+                name: format!("Track {}", id),
+                artists: vec!["Artist".to_string()],
+                isrc: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_track_lists_sections() {
+        let a = summaries(&["1", "2", "3", "2"]);
+        let b = summaries(&["3", "4", "2", "4"]);
+
+        let diff = Auditor::diff_track_lists(&a, &b, false);
+
+        let ids = |tracks: &[TrackSummary]| tracks.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&diff.only_in_a), vec!["1"]);
+        assert_eq!(ids(&diff.only_in_b), vec!["4"]);
+        assert_eq!(ids(&diff.in_both), vec!["2", "3"]);
+    }
+
+    #[test]
+    fn test_diff_track_lists_matches_isrc() {
+        let mut a = summaries(&["1", "2"]);
+        let mut b = summaries(&["9"]);
+        a[0].isrc = Some("USABC0000001".to_string());
+        b[0].isrc = Some("USABC0000001".to_string());
+
+        let by_id = Auditor::diff_track_lists(&a, &b, false);
+        assert_eq!(by_id.in_both.len(), 0);
+
+        let by_isrc = Auditor::diff_track_lists(&a, &b, true);
+        assert_eq!(by_isrc.in_both.len(), 1);
+        assert_eq!(by_isrc.in_both[0].id, "1");
+        assert_eq!(by_isrc.only_in_a.len(), 1);
+        assert!(by_isrc.only_in_b.is_empty());
+    }
+
+    #[test]
+    fn test_track_inspection_keeps_linked_from() {
+        let mut track = track_with_markets("4iV5W9uYEdYUVa79Axb7Rh", &[]);
+        assert_eq!(track_inspection(track.clone()).linked_from, None);
+
+        track.linked_from = serde_json::from_value(serde_json::json!({
+            "external_urls": {},
+            "href": "",
+            "id": "1301WleyT98MSxVHPZCA6M",
+            "type": "track",
+            "uri": "spotify:track:1301WleyT98MSxVHPZCA6M"
+        }))
+        .unwrap();
+        assert_eq!(
+            track_inspection(track).linked_from.as_deref(),
+            Some("spotify:track:1301WleyT98MSxVHPZCA6M")
+        );
+    }
+
+    #[test]
+    fn test_set_my_market() {
+        let mut track = track_inspection(track_with_markets("4iV5W9uYEdYUVa79Axb7Rh", &["US"]));
+        track.set_my_market(Some("US"));
+        assert_eq!(track.available_in_my_market, Some(true));
+        track.set_my_market(Some("MX"));
+        assert_eq!(track.available_in_my_market, Some(false));
+        assert_eq!(track.my_market.as_deref(), Some("MX"));
+        track.set_my_market(None);
+        assert_eq!(track.available_in_my_market, None);
+
+        // A market lookup has no market list, only `is_playable`.
+        let mut track = track_inspection(track_with_markets("4iV5W9uYEdYUVa79Axb7Rh", &[]));
+        track.is_playable = Some(true);
+        track.set_my_market(Some("MX"));
+        assert_eq!(track.available_in_my_market, Some(true));
+    }
+
+    #[test]
+    fn test_group_by_name_exact() {
+        let tracks = vec![
+            inspection("4iV5W9uYEdYUVa79Axb7Rh", "Yesterday", "The Beatles"),
+            inspection("1301WleyT98MSxVHPZCA6M", "yesterday ", "the beatles"),
+            inspection(
+                "2TpxZ7JUBn3uw46aR7qd6V",
+                "Yesterday - Remastered",
+                "The Beatles",
+            ),
+            inspection("3n3Ppam7vgaVa1iaRUc9Lp", "Yesterday", "Other Artist"),
+            // Same ID liked twice is not a duplicate group on its own.
+            inspection("3n3Ppam7vgaVa1iaRUc9Lp", "Yesterday", "Other Artist"),
+        ];
+
+        let groups = group_by_name(tracks, 1.0);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].artists, "the beatles");
+        assert_eq!(groups[0].tracks.len(), 2);
+    }
+
+    #[test]
+    fn test_group_by_name_fuzzy() {
+        let tracks = vec![
+            inspection("4iV5W9uYEdYUVa79Axb7Rh", "Song (Remastered)", "Artist"),
+            inspection("1301WleyT98MSxVHPZCA6M", "Song (Remaster)", "Artist"),
+            inspection("2TpxZ7JUBn3uw46aR7qd6V", "Completely Different", "Artist"),
+        ];
+
+        let groups = group_by_name(tracks, 0.8);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].tracks.len(), 2);
+    }
+
+    fn occurrence(isrc: &str, playlist: &str, track: &str) -> (String, IsrcOccurrence) {
+        (
+            isrc.to_string(),
+            IsrcOccurrence {
+                playlist_id: playlist.to_string(),
+                playlist_name: format!("Playlist {}", playlist),
+                track_id: track.to_string(),
+                track_name: "Track".to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_isrc_clusters_need_two_playlists_and_two_ids() {
+        let clusters = isrc_clusters(vec![
+            // Same ISRC, different IDs, different playlists: a cluster.
+            occurrence("ISRC1", "a", "t1"),
+            occurrence("ISRC1", "b", "t2"),
+            // Same ID in two playlists: not a duplicate under different IDs.
+            occurrence("ISRC2", "a", "t3"),
+            occurrence("ISRC2", "b", "t3"),
+            // Different IDs but within a single playlist: not cross-playlist.
+            occurrence("ISRC3", "a", "t4"),
+            occurrence("ISRC3", "a", "t5"),
+        ]);
+
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].isrc, "ISRC1");
+        assert_eq!(clusters[0].occurrences.len(), 2);
+    }
+
+    #[test]
+    fn test_diff_audits_fixed_and_regressed() {
+        let before = summary(&["a", "b"]);
+        let after = summary(&["b", "c"]);
+
+        let diff = Auditor::diff_audits(&before, &after);
+        assert_eq!(diff.fixed.len(), 1);
+        assert_eq!(diff.fixed[0].id, "a");
+        assert_eq!(diff.regressed.len(), 1);
+        assert_eq!(diff.regressed[0].id, "c");
+    }
+
+    #[test]
+    fn test_diff_audits_unchanged() {
+        let diff = Auditor::diff_audits(&summary(&["a"]), &summary(&["a"]));
+        assert!(diff.fixed.is_empty());
+        assert!(diff.regressed.is_empty());
+    }
+
+    #[test]
+    fn test_parse_restore_ids_from_backup() {
+        let content = r#"[
+            {"id": "spotify:track:a", "name": "A", "artists": [], "album": "X",
+             "isrc": null, "added_at": "2024-01-01T00:00:00+00:00", "available_markets": []},
+            {"id": "spotify:track:b", "name": "B", "artists": [], "album": "Y",
+             "isrc": "US123", "added_at": "2024-01-02T00:00:00+00:00", "available_markets": ["MX"]}
+        ]"#;
+        assert_eq!(
+            parse_restore_ids(content).unwrap(),
+            vec!["spotify:track:a", "spotify:track:b"]
+        );
+    }
+
+    #[test]
+    fn test_newly_unavailable() {
+        let backed_up = |id: &str, is_playable: Option<bool>, markets: &[&str]| BackupTrack {
+            id: format!("spotify:track:{}", id),
+            name: "Track".to_string(),
+            artists: vec![],
+            album: "Album".to_string(),
+            isrc: None,
+            added_at: "2024-01-01T00:00:00+00:00".to_string(),
+            available_markets: markets.iter().map(|m| m.to_string()).collect(),
+            is_playable,
+        };
+        let now = |id: &str, markets: &[&str]| {
+            let mut track = track_inspection(track_with_markets(id, markets));
+            track.set_my_market(Some("MX"));
+            track
+        };
+        let backup = [
+            backed_up("4uLU6hMCjMI75M1A2tKUQC", Some(true), &[]),
+            backed_up("3n3Ppam7vgaVa1iaRUc9Lp", None, &["MX", "US"]),
+            backed_up("7ouMYWpwJ422jRcDASZB7P", Some(false), &[]),
+            backed_up("0VjIjW4GlUZAMYd2vXMi3b", None, &["MX"]),
+            // Already unavailable when the market-less backup was taken.
+            backed_up("1301WleyT98MSxVHPZCA6M", None, &[]),
+            backed_up("2takcwOaAZWiXQijPHIx7B", None, &["US"]),
+        ];
+        let current = vec![
+            now("4uLU6hMCjMI75M1A2tKUQC", &["US"]),
+            now("3n3Ppam7vgaVa1iaRUc9Lp", &[]),
+            now("7ouMYWpwJ422jRcDASZB7P", &[]),
+            now("0VjIjW4GlUZAMYd2vXMi3b", &["MX"]),
+            now("1301WleyT98MSxVHPZCA6M", &[]),
+            now("2takcwOaAZWiXQijPHIx7B", &["US"]),
+        ];
+
+        let gone = newly_unavailable(&backup, current, Some("MX"));
+        let ids: Vec<&str> = gone.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "spotify:track:4uLU6hMCjMI75M1A2tKUQC",
+                "spotify:track:3n3Ppam7vgaVa1iaRUc9Lp"
+            ]
+        );
+        assert_eq!(gone[0].kind, ProblemKind::GeoLocked);
+        assert_eq!(gone[1].kind, ProblemKind::GloballyRemoved);
+    }
+
+    #[test]
+    fn test_parse_restore_ids_from_library_backup() {
+        let backup = LibraryBackup {
+            saved_at: "2025-01-01T00:00:00+00:00".to_string(),
+            user_id: "someone".to_string(),
+            tracks: vec![BackupTrack {
+                id: "spotify:track:a".to_string(),
+                name: "A".to_string(),
+                artists: vec!["Artist".to_string()],
+                album: "Album".to_string(),
+                isrc: None,
+                added_at: "2024-01-01T00:00:00+00:00".to_string(),
+                available_markets: vec![],
+                is_playable: None,
+            }],
+        };
+        let content = serde_json::to_string(&backup).unwrap();
+        assert_eq!(
+            parse_restore_ids(&content).unwrap(),
+            vec!["spotify:track:a"]
+        );
+    }
+
+    #[test]
+    fn test_parse_restore_ids_from_audit_summary() {
+        let content = serde_json::to_string(&summary(&["a", "b"])).unwrap();
+        assert_eq!(parse_restore_ids(&content).unwrap(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_parse_restore_ids_rejects_unknown_layout() {
+        assert!(parse_restore_ids(r#"{"items": []}"#).is_err());
+        assert!(parse_restore_ids("not json").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_backoff_does_not_retry_permanent_errors() {
+        let mut calls = 0;
+        let result: Result<(), AuditError> = retry_with_backoff(
+            || {
+                calls += 1;
+                async { Err(ClientError::InvalidToken) }
+            },
+            3,
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(AuditError::Spotify(ClientError::InvalidToken))
+        ));
+        assert_eq!(calls, 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_backoff_returns_success() {
+        let result = retry_with_backoff(|| async { Ok::<_, ClientError>(42) }, 3).await;
+        assert_eq!(result.unwrap(), 42);
+    }
+
+    #[test]
+    fn test_m3u_entry() {
+        let mut track = track_with_markets("4uLU6hMCjMI75M1A2tKUQC", &[]);
+        assert_eq!(
+            m3u_entry(&track).unwrap(),
+            "#EXTINF:180,Track\nspotify:track:4uLU6hMCjMI75M1A2tKUQC\n"
+        );
+
+        track.duration = chrono::Duration::zero();
+        track.external_urls.insert(
+            "spotify".to_string(),
+            "https://open.spotify.com/track/4uLU6hMCjMI75M1A2tKUQC".to_string(),
+        );
+        assert_eq!(
+            m3u_entry(&track).unwrap(),
+            "#EXTINF:-1,Track\nhttps://open.spotify.com/track/4uLU6hMCjMI75M1A2tKUQC\n"
+        );
+
+        track.id = None;
+        track.external_urls.clear();
+        assert_eq!(m3u_entry(&track), None);
+    }
+
+    #[test]
+    fn test_cross_reference_artists() {
+        let followed = vec![
+            ("1".to_string(), "Queen".to_string()),
+            ("2".to_string(), "blur".to_string()),
+            ("3".to_string(), "ABBA".to_string()),
+        ];
+        let saved = [("4", "Oasis", 7), ("1", "Queen", 5), ("5", "Pulp", 1)]
+            .into_iter()
+            .map(|(id, name, saved_tracks)| ArtistRef {
+                id: id.to_string(),
+                name: name.to_string(),
+                saved_tracks,
+            })
+            .collect();
+
+        let report = cross_reference_artists(followed, saved);
+        assert_eq!(report.followed_total, 3);
+        let names = |artists: &[ArtistRef]| -> Vec<String> {
+            artists.iter().map(|a| a.name.clone()).collect()
+        };
+        assert_eq!(names(&report.followed_without_saves), ["ABBA", "blur"]);
+        assert_eq!(names(&report.saved_not_followed), ["Oasis", "Pulp"]);
+        assert_eq!(report.saved_not_followed[0].saved_tracks, 7);
+    }
+
+    #[test]
+    fn test_cap_to_playlist_limit() {
+        let mut ids: Vec<usize> = (0..10).collect();
+        assert!(cap_to_playlist_limit(&mut ids, 0).is_empty());
+        assert_eq!(ids.len(), 10);
+
+        let dropped = cap_to_playlist_limit(&mut ids, PLAYLIST_TRACK_LIMIT - 4);
+        assert_eq!(ids, [0, 1, 2, 3]);
+        assert_eq!(dropped, [4, 5, 6, 7, 8, 9]);
+
+        let mut full: Vec<usize> = vec![1, 2];
+        assert_eq!(
+            cap_to_playlist_limit(&mut full, PLAYLIST_TRACK_LIMIT + 1),
+            [1, 2]
+        );
+        assert!(full.is_empty());
+    }
+
+    #[test]
+    fn test_album_gaps() {
+        let requested = AlbumId::from_id("4aawyAB9vmqN3uQ7FjRGTy").unwrap();
+        let canonical = AlbumId::from_id("6akEvsycLGftJxYudPjmqK").unwrap();
+        let saved = HashMap::from([(requested.clone(), HashSet::from([(1, 1), (1, 3)]))]);
+        let positions = vec![(1, 1), (1, 2), (1, 3), (2, 1)];
+
+        assert_eq!(
+            album_gaps(&saved, &requested, positions.clone()),
+            Some((2, vec![(1, 2), (2, 1)]))
+        );
+        assert_eq!(album_gaps(&saved, &canonical, positions), None);
+    }
+
+    #[test]
+    fn test_rank_artists_orders_by_count_then_name() {
+        let artists = HashMap::from([
+            ("1".to_string(), ("queen".to_string(), 2)),
+            ("2".to_string(), ("ABBA".to_string(), 2)),
+            ("3".to_string(), ("Blur".to_string(), 5)),
+        ]);
+        let names: Vec<String> = rank_artists(artists)
+            .into_iter()
+            .map(|artist| artist.name)
+            .collect();
+        assert_eq!(names, ["Blur", "ABBA", "queen"]);
+    }
+
+    #[tokio::test]
+    async fn test_with_config_clamps_concurrency_and_delays_requests() {
+        let config = AuditorConfig {
+            max_concurrency: 0,
+            max_retries: 1,
+            request_delay_ms: 20,
+            ..Default::default()
+        };
+        let auditor = Auditor::with_config(AuthCodeSpotify::default(), config);
+        assert_eq!(auditor.config.max_concurrency, 1);
+        assert_eq!(auditor.config.max_retries, 1);
+
+        let started = std::time::Instant::now();
+        let result = auditor.retry(|| async { Ok::<_, ClientError>(7) }).await;
+        assert_eq!(result.unwrap(), 7);
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    }
+
+    fn http_error(status: u16, retry_after: Option<&str>) -> ClientError {
+        let mut response = http::Response::builder().status(status);
+        if let Some(value) = retry_after {
+            response = response.header("Retry-After", value);
+        }
+        let response = reqwest::Response::from(response.body("").unwrap());
+        ClientError::Http(Box::new(HttpError::StatusCode(response)))
+    }
+
+    fn playlist(name: &str, tracks: u32, owner: &str, collaborative: bool) -> PlaylistSummary {
+        PlaylistSummary {
+            id: format!("spotify:playlist:{}", name),
+            name: name.to_string(),
+            total_tracks: tracks,
+            is_public: !collaborative,
+            is_collaborative: collaborative,
+            owner_name: owner.to_uppercase(),
+            owner_id: owner.to_string(),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_playlist_track_count_reads_filtered_response() {
+        let count: PlaylistTrackCount =
+            serde_json::from_str(r#"{"tracks":{"total":1234}}"#).unwrap();
+        assert_eq!(count.tracks.total, 1234);
+    }
+
+    #[test]
+    fn test_listed_playlist_keeps_description() {
+        let item = |description: &str| {
+            format!(
+                r#"{{"collaborative":false,"description":{},"external_urls":{{}},
+                "href":"","id":"37i9dQZF1DXcBWIGoYBM5M","images":null,"name":"Mix",
+                "owner":{{"display_name":null,"external_urls":{{}},"href":"","id":"me"}},
+                "public":true,"snapshot_id":"s","tracks":{{"href":"","total":3}}}}"#,
+                description
+            )
+        };
+
+        let listed: ListedPlaylist = serde_json::from_str(&item(r#""Road trip""#)).unwrap();
+        assert_eq!(listed.playlist.name, "Mix");
+        assert_eq!(listed.playlist.tracks.total, 3);
+        assert_eq!(non_empty(listed.description).as_deref(), Some("Road trip"));
+
+        let listed: ListedPlaylist = serde_json::from_str(&item(r#""""#)).unwrap();
+        assert_eq!(non_empty(listed.description), None);
+    }
+
+    #[test]
+    fn test_sort_and_filter_playlists() {
+        let playlists = || {
+            vec![
+                playlist("rock", 10, "me", false),
+                playlist("Jazz", 30, "friend", false),
+                playlist("party", 20, "me", true),
+            ]
+        };
+        let names = |playlists: Vec<PlaylistSummary>| -> Vec<String> {
+            playlists.into_iter().map(|pl| pl.name).collect()
+        };
+        let all = PlaylistFilter::default();
+
+        let sorted = sort_and_filter_playlists(playlists(), PlaylistSortKey::Name, all, "me");
+        assert_eq!(names(sorted), ["Jazz", "party", "rock"]);
+        let sorted = sort_and_filter_playlists(playlists(), PlaylistSortKey::TrackCount, all, "me");
+        assert_eq!(names(sorted), ["Jazz", "party", "rock"]);
+        let sorted = sort_and_filter_playlists(playlists(), PlaylistSortKey::Owner, all, "me");
+        assert_eq!(names(sorted), ["Jazz", "party", "rock"]);
+
+        let owned = PlaylistFilter {
+            owned_only: true,
+            ..Default::default()
+        };
+        let sorted = sort_and_filter_playlists(playlists(), PlaylistSortKey::Name, owned, "me");
+        assert_eq!(names(sorted), ["party", "rock"]);
+
+        let public_collab = PlaylistFilter {
+            collaborative_only: true,
+            public_only: true,
+            ..Default::default()
+        };
+        let sorted =
+            sort_and_filter_playlists(playlists(), PlaylistSortKey::Name, public_collab, "me");
+        assert!(sorted.is_empty());
+    }
+
+    #[test]
+    fn test_saves_per_month() {
+        let id = TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap();
+        let entry = |added_at: &str| {
+            let added_at = DateTime::parse_from_rfc3339(added_at).unwrap();
+            (
+                added_at.with_timezone(&Utc),
+                id.clone(),
+                "Track".to_string(),
+            )
+        };
+        let timeline = vec![
+            entry("2023-12-31T23:59:59Z"),
+            entry("2024-01-01T00:00:00Z"),
+            entry("2024-01-31T12:00:00Z"),
+        ];
+
+        let months = Auditor::saves_per_month(&timeline);
+        assert_eq!(
+            months.into_iter().collect::<Vec<_>>(),
+            vec![("2023-12".to_string(), 1), ("2024-01".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_honours_retry_after() {
+        let error = http_error(429, Some("7"));
+        assert_eq!(retry_delay(&error, 0), Some(Duration::from_secs(7)));
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_without_header() {
+        assert_eq!(
+            retry_delay(&http_error(429, None), 2),
+            Some(Duration::from_secs(4))
+        );
+        assert_eq!(
+            retry_delay(&http_error(503, None), 1),
+            Some(Duration::from_secs(2))
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_ignores_client_errors() {
+        assert_eq!(retry_delay(&http_error(404, None), 0), None);
+        assert_eq!(retry_delay(&ClientError::InvalidToken, 0), None);
+    }
+
+    #[test]
+    fn test_availability_problem_checks_markets_without_playability() {
+        let markets = vec!["US".to_string(), "CA".to_string()];
+        assert_eq!(
+            availability_problem(None, false, &markets, Some("US")),
+            None
+        );
+        assert_eq!(
+            availability_problem(None, false, &markets, Some("MX")).as_deref(),
+            Some("Track is not available in market MX")
+        );
+        assert_eq!(
+            availability_problem(None, false, &[], None).as_deref(),
+            Some(NO_MARKETS_REASON)
+        );
+        assert_eq!(availability_problem(None, false, &markets, None), None);
+        assert_eq!(
+            availability_problem(Some(true), false, &[], Some("MX")),
+            None
+        );
+        assert_eq!(
+            availability_problem(Some(false), false, &markets, None).as_deref(),
+            Some(UNPLAYABLE_REASON)
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_becomes_its_own_error() {
+        assert!(matches!(
+            AuditError::from(http_error(429, Some("7"))),
+            AuditError::RateLimit {
+                retry_after_secs: 7
+            }
+        ));
+        assert!(matches!(
+            AuditError::from(http_error(404, None)),
+            AuditError::Spotify(_)
+        ));
+    }
+
+    #[test]
+    fn test_is_auth_failure() {
+        assert!(AuditError::Spotify(ClientError::InvalidToken).is_auth_failure());
+        assert!(AuditError::from(http_error(401, None)).is_auth_failure());
+        assert!(!AuditError::from(http_error(503, None)).is_auth_failure());
+        assert!(!AuditError::InvalidId("x".to_string()).is_auth_failure());
+    }
+
+    #[test]
+    fn test_is_permanent() {
+        assert!(AuditError::InvalidId("x".to_string()).is_permanent());
+        assert!(AuditError::from(http_error(404, None)).is_permanent());
+        assert!(AuditError::from(http_error(403, None))
+            .context("failed on playlist abc")
+            .is_permanent());
+        assert!(!AuditError::from(http_error(401, None)).is_permanent());
+        assert!(!AuditError::from(http_error(429, None)).is_permanent());
+        assert!(!AuditError::from(http_error(503, None)).is_permanent());
+    }
+
+    #[test]
+    fn test_context_names_the_object_and_keeps_rate_limits() {
+        let error = AuditError::from(http_error(401, None)).context("failed on playlist abc");
+        assert!(error.to_string().starts_with("failed on playlist abc: "));
+        assert!(error.is_auth_failure());
+
+        let ids = [
+            TrackId::from_id("4uLU6hMCjMI75M1A2tKUQC").unwrap(),
+            TrackId::from_id("1301WleyT98MSxVHPZCA6M").unwrap(),
+        ];
+        assert_eq!(
+            tracks_context(&ids),
+            "failed on tracks spotify:track:4uLU6hMCjMI75M1A2tKUQC, spotify:track:1301WleyT98MSxVHPZCA6M"
+        );
+
+        let limited = AuditError::RateLimit {
+            retry_after_secs: 3,
+        }
+        .context("ignored");
+        assert!(matches!(
+            limited,
+            AuditError::RateLimit {
+                retry_after_secs: 3
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_chunks_keeps_order_and_bounds_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+
+        let (in_flight, peak) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let items: Vec<u64> = (0..10).collect();
+        let doubled = fetch_chunks(&items, 3, 2, |chunk| {
+            let (in_flight, peak) = (&in_flight, &peak);
+            async move {
+                peak.fetch_max(in_flight.fetch_add(1, SeqCst) + 1, SeqCst);
+                // Later chunks answer first.
+                tokio::time::sleep(Duration::from_millis(10 * (10 - chunk[0]))).await;
+                in_flight.fetch_sub(1, SeqCst);
+                Ok(chunk.iter().map(|n| n * 2).collect())
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(doubled, (0..10).map(|n| n * 2).collect::<Vec<_>>());
+        assert_eq!(peak.load(SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_chunks_returns_the_error() {
+        let result: Result<Vec<i32>, _> = fetch_chunks(&[1, 2, 3, 4], 1, 2, |chunk| async move {
+            if chunk[0] == 3 {
+                Err(AuditError::InvalidTrackId("3".to_string()))
+            } else {
+                Ok(chunk.to_vec())
+            }
+        })
+        .await;
+        assert!(matches!(result, Err(AuditError::InvalidTrackId(id)) if id == "3"));
+    }
+
+    #[test]
+    fn test_unplayable_kind_waits_for_markets_of_market_scans() {
+        let mut track = track_with_markets("4uLU6hMCjMI75M1A2tKUQC", &[]);
+        assert_eq!(unplayable_kind(&track), ProblemKind::GloballyRemoved);
+
+        track.is_playable = Some(false);
+        assert_eq!(unplayable_kind(&track), ProblemKind::Unplayable);
+
+        let mut problem =
+            Auditor::new(AuthCodeSpotify::default()).create_problem_report(&track, "x");
+        problem.set_available_markets(vec!["US".to_string()]);
+        assert_eq!(problem.kind, ProblemKind::GeoLocked);
+    }
+
+    #[test]
+    fn test_explicit_only_reports_explicit_tracks() {
+        let clean = track_with_markets("4uLU6hMCjMI75M1A2tKUQC", &[]);
+        let mut explicit = track_with_markets("1301WleyT98MSxVHPZCA6M", &["US"]);
+        explicit.explicit = true;
+        assert_eq!(explicit_problem(&clean), None);
+        assert_eq!(explicit_problem(&explicit), Some(EXPLICIT_REASON));
+
+        let mut auditor = Auditor::new(AuthCodeSpotify::default());
+        auditor.set_explicit_only(true);
+        // Unplayable but not explicit: not reported in this mode.
+        assert!(auditor.analyze_track(&clean, None).is_none());
+        let problem = auditor.analyze_track(&explicit, Some("US")).unwrap();
+        assert_eq!(problem.kind, ProblemKind::Explicit);
+        assert_eq!(problem.reason, "Explicit content");
+        assert_eq!(problem.status_with_color(false), "[EXPLICIT]");
+
+        auditor.set_explicit_only(false);
+        assert!(auditor.analyze_track(&clean, None).is_some());
+        assert!(auditor.analyze_track(&explicit, Some("US")).is_none());
+    }
+
+    /// Writer whose bytes stay readable after it was handed to the auditor.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
         }
     }
+
+    #[test]
+    fn test_record_problem_streams_ndjson() {
+        let buffer = SharedBuffer::default();
+        let mut auditor = Auditor::new(AuthCodeSpotify::default());
+        auditor.set_problem_writer(Box::new(buffer.clone()));
+
+        let mut summary = AuditSummary::new();
+        auditor
+            .record_problem(&mut summary, problem("1", 0))
+            .unwrap();
+        auditor
+            .record_problem(&mut summary, problem("2", 0))
+            .unwrap();
+
+        assert_eq!(summary.problematic_tracks.len(), 2);
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let ids: Vec<String> = output
+            .lines()
+            .map(|line| serde_json::from_str::<ProblematicTrack>(line).unwrap().id)
+            .collect();
+        assert_eq!(ids, vec!["1", "2"]);
+    }
 }
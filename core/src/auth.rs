@@ -16,8 +16,27 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use rspotify::{prelude::*, scopes, AuthCodeSpotify, Config, Credentials, OAuth};
+use rspotify::{prelude::*, scopes, AuthCodeSpotify, Config, Credentials, OAuth, Token};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
+use tracing::{info, warn};
+
+/// Environment variable with a refresh token to authenticate without a browser.
+pub const REFRESH_TOKEN_ENV: &str = "RSPOTIFY_REFRESH_TOKEN";
+
+/// Environment variable overriding where the OAuth token is cached.
+pub const TOKEN_CACHE_ENV: &str = "SPOTIFY_AUDIT_CACHE_PATH";
+
+/// Where rspotify caches the token by default, relative to the working directory.
+const LEGACY_TOKEN_CACHE: &str = ".spotify_token_cache.json";
+
+/// Environment variables that must be set (e.g. in `.env`) to build a client.
+pub const REQUIRED_ENV_VARS: [&str; 3] = [
+    "RSPOTIFY_CLIENT_ID",
+    "RSPOTIFY_CLIENT_SECRET",
+    "RSPOTIFY_REDIRECT_URI",
+];
 
 #[derive(Error, Debug)]
 pub enum AuthError {
@@ -25,6 +44,10 @@ pub enum AuthError {
     ClientConfig(String),
     #[error("Spotify authentication failed: {0}")]
     Spotify(#[from] rspotify::ClientError),
+    #[error(
+        "No Spotify token available: authorize once interactively or set RSPOTIFY_REFRESH_TOKEN"
+    )]
+    NoToken,
 }
 
 /// Initializes and authenticates a Spotify client using the Authorization Code Flow.
@@ -32,53 +55,174 @@ pub enum AuthError {
 /// This function:
 /// 1. Reads credentials (`RSPOTIFY_CLIENT_ID`, `RSPOTIFY_CLIENT_SECRET`) from the environment.
 /// 2. Reads the redirect URI (`RSPOTIFY_REDIRECT_URI`) from the environment.
-/// 3. Requests necessary scopes for auditing (library read/write, playlist read).
+/// 3. Requests necessary scopes for auditing (library read/write, playlist read/write).
 /// 4. Handles the OAuth2 flow, including token caching and refreshing.
 ///
-/// If a valid token is not cached, it will prompt the user (via stdout) to visit a URL
-/// to authorize the application.
+/// A cached token or a refresh token in `RSPOTIFY_REFRESH_TOKEN` is used silently.
+/// Only when neither works will it prompt the user (via stdout) to visit a URL to
+/// authorize the application.
 pub async fn get_spotify_client() -> Result<AuthCodeSpotify, AuthError> {
-    // Load credentials from env. `rspotify` expects RSPOTIFY_CLIENT_ID/SECRET.
-    let creds = Credentials::from_env().ok_or_else(|| {
-        AuthError::ClientConfig("Missing RSPOTIFY_CLIENT_ID or RSPOTIFY_CLIENT_SECRET".to_string())
-    })?;
+    let spotify = build_client()?;
+
+    match load_token_silently(&spotify).await {
+        Ok(true) => return Ok(spotify),
+        Ok(false) => {}
+        Err(e) => warn!(error = %e, "could not reuse the stored token, prompting instead"),
+    }
 
-    // Define scopes required for the audit functionality.
+    // Get the authorization URL.
+    let url = spotify.get_authorize_url(false)?;
+
+    // This method from the `cli` feature of rspotify handles the interaction:
+    // 1. Tries to open the URL in the default browser.
+    // 2. If that fails, prints the URL to stdout.
+    // 3. Waits for the redirect URI to be hit (if running a local server) or input.
+    // Note: Since we are using a localhost redirect, rspotify usually spins up a tiny server
+    // to catch the callback if the port matches the redirect URI.
+    spotify.prompt_for_token(&url).await?;
+
+    Ok(spotify)
+}
+
+/// Like `get_spotify_client`, but never prompts: without a cached token or
+/// `RSPOTIFY_REFRESH_TOKEN` it fails with `AuthError::NoToken`. Meant for cron jobs
+/// and CI, where nobody can open the authorization URL.
+pub async fn get_spotify_client_headless() -> Result<AuthCodeSpotify, AuthError> {
+    let spotify = build_client()?;
+    if load_token_silently(&spotify).await? {
+        Ok(spotify)
+    } else {
+        Err(AuthError::NoToken)
+    }
+}
+
+/// Where the OAuth token is cached: `SPOTIFY_AUDIT_CACHE_PATH` when set, else
+/// `$XDG_CONFIG_HOME/spotify-audit/token.json` (falling back to `~/.config`), else
+/// rspotify's `.spotify_token_cache.json` in the working directory.
+///
+/// The config directory is resolved by hand (Linux conventions on every platform)
+/// to avoid a dependency just for this path.
+pub fn token_cache_path() -> PathBuf {
+    if let Some(path) = std::env::var_os(TOKEN_CACHE_ENV).filter(|path| !path.is_empty()) {
+        return PathBuf::from(path);
+    }
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        .map(|base| base.join("spotify-audit").join("token.json"))
+        .unwrap_or_else(|| PathBuf::from(LEGACY_TOKEN_CACHE))
+}
+
+/// Moves a token cached by an older version in the working directory to `path`, so
+/// upgrading does not ask for authorization again. Does nothing once `path` exists.
+fn migrate_legacy_token(path: &Path) {
+    let legacy = Path::new(LEGACY_TOKEN_CACHE);
+    if path.exists() || !legacy.is_file() || path == legacy {
+        return;
+    }
+    // `rename` fails across file systems; copying leaves the old file in place.
+    let moved = std::fs::rename(legacy, path).or_else(|_| std::fs::copy(legacy, path).map(|_| ()));
+    match moved {
+        Ok(()) => info!(from = %legacy.display(), to = %path.display(), "moved the cached token"),
+        Err(e) => warn!(path = %legacy.display(), error = %e, "could not move the cached token"),
+    }
+}
+
+/// Scopes required for the audit functionality.
+pub fn required_scopes() -> HashSet<String> {
     // - user-library-read: To check Liked Songs.
     // - user-library-modify: To add songs to Liked Songs (sync feature).
     // - playlist-read-private: To read user's private playlists.
     // - playlist-read-collaborative: To read collaborative playlists.
-    let scopes = scopes!(
+    // - playlist-modify-public/private: To remove dead tracks from playlists.
+    // - user-read-private: To read the account country and subscription (`me`).
+    // - user-follow-read: To list followed artists (`followed-artists`).
+    scopes!(
         "user-library-read",
         "user-library-modify",
         "playlist-read-private",
-        "playlist-read-collaborative"
-    );
+        "playlist-read-collaborative",
+        "playlist-modify-public",
+        "playlist-modify-private",
+        "user-read-private",
+        "user-follow-read"
+    )
+}
+
+/// Checks that the token works by calling `current_user()` and returns the required
+/// scopes it was not granted, sorted. An empty list means the token is fine.
+pub async fn verify_scopes(spotify: &AuthCodeSpotify) -> Result<Vec<String>, AuthError> {
+    spotify.current_user().await?;
+
+    let granted = match spotify.get_token().lock().await.unwrap().as_ref() {
+        Some(token) => token.scopes.clone(),
+        None => return Err(AuthError::NoToken),
+    };
+    let mut missing: Vec<String> = required_scopes().difference(&granted).cloned().collect();
+    missing.sort();
+    Ok(missing)
+}
+
+/// Builds the client from the environment, without any token yet.
+fn build_client() -> Result<AuthCodeSpotify, AuthError> {
+    // Load credentials from env. `rspotify` expects RSPOTIFY_CLIENT_ID/SECRET.
+    let creds = Credentials::from_env().ok_or_else(|| {
+        AuthError::ClientConfig("Missing RSPOTIFY_CLIENT_ID or RSPOTIFY_CLIENT_SECRET".to_string())
+    })?;
 
     // Load OAuth config (Redirect URI) from env.
-    let oauth = OAuth::from_env(scopes)
+    let oauth = OAuth::from_env(required_scopes())
         .ok_or_else(|| AuthError::ClientConfig("Missing RSPOTIFY_REDIRECT_URI".to_string()))?;
 
     // Configure the client.
-    // `token_cached: true` enables saving the token to a file (default: .spotify_token_cache.json).
+    // `token_cached: true` enables saving the token to `token_cache_path()`.
+    let cache_path = token_cache_path();
+    if let Some(dir) = cache_path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+    {
+        // rspotify does not create the directory; without it the token is just not saved.
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            warn!(path = %dir.display(), error = %e, "could not create the token cache directory");
+        }
+    }
+    migrate_legacy_token(&cache_path);
     let config = Config {
         token_cached: true,
         token_refreshing: true,
+        cache_path,
         ..Default::default()
     };
 
-    let spotify = AuthCodeSpotify::with_config(creds, oauth, config);
-
-    // Get the authorization URL.
-    let url = spotify.get_authorize_url(false)?;
+    Ok(AuthCodeSpotify::with_config(creds, oauth, config))
+}
 
-    // This method from the `cli` feature of rspotify handles the interaction:
-    // 1. Tries to open the URL in the default browser.
-    // 2. If that fails, prints the URL to stdout.
-    // 3. Waits for the redirect URI to be hit (if running a local server) or input.
-    // Note: Since we are using a localhost redirect, rspotify usually spins up a tiny server
-    // to catch the callback if the port matches the redirect URI.
-    spotify.prompt_for_token(&url).await?;
+/// Loads the cached token, or else a token built from `RSPOTIFY_REFRESH_TOKEN`, and
+/// refreshes it when expired. Returns `false` when there is neither.
+async fn load_token_silently(spotify: &AuthCodeSpotify) -> Result<bool, AuthError> {
+    // A missing or unreadable cache file is an error here; treat it as no token.
+    let token = match spotify.read_token_cache(true).await.ok().flatten() {
+        Some(token) => token,
+        None => match std::env::var(REFRESH_TOKEN_ENV) {
+            Ok(refresh_token) if !refresh_token.trim().is_empty() => {
+                info!("authenticating with the refresh token from the environment");
+                // The default token is already expired, which forces the refresh below.
+                Token {
+                    refresh_token: Some(refresh_token.trim().to_string()),
+                    scopes: spotify.get_oauth().scopes.clone(),
+                    ..Default::default()
+                }
+            }
+            _ => return Ok(false),
+        },
+    };
 
-    Ok(spotify)
+    let expired = token.is_expired();
+    *spotify.get_token().lock().await.unwrap() = Some(token);
+    if expired {
+        // Also writes the refreshed token to the cache for the next run.
+        spotify.refresh_token().await?;
+    }
+    Ok(true)
 }
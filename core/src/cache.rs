@@ -0,0 +1,192 @@
+/*
+    spotify-audit-rs | Rust CLI tool to audit playlists and sync Liked Songs.
+    Copyright (C) 2025  Israel Alberto Roldan Vega
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::models::{TrackInspection, SCHEMA_VERSION};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::warn;
+
+/// File name of the track cache inside the cache directory.
+const CACHE_FILE: &str = "tracks.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    fetched_at: i64, // Unix timestamp (seconds)
+    track: TrackInspection,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    entries: HashMap<String, CacheEntry>,
+}
+
+/// On-disk cache of full-track lookups, keyed by track URI.
+///
+/// Entries older than the TTL are treated as missing. A missing or unreadable file
+/// starts an empty cache, so the cache can never make a command fail.
+pub struct TrackCache {
+    path: PathBuf,
+    ttl: Duration,
+    file: CacheFile,
+}
+
+impl TrackCache {
+    /// Loads the cache stored at `path`.
+    pub fn open(path: impl Into<PathBuf>, ttl: Duration) -> Self {
+        let path = path.into();
+        let file = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!(path = %path.display(), error = %e, "ignoring unreadable track cache");
+                CacheFile::default()
+            }),
+            Err(_) => CacheFile::default(),
+        };
+        Self { path, ttl, file }
+    }
+
+    /// `$XDG_CACHE_HOME/spotify-audit/tracks.json`, falling back to `~/.cache`.
+    pub fn default_path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))?;
+        Some(base.join("spotify-audit").join(CACHE_FILE))
+    }
+
+    /// Returns the cached track for `uri` unless it is older than the TTL or was
+    /// written with a different `SCHEMA_VERSION`.
+    pub fn get(&self, uri: &str) -> Option<TrackInspection> {
+        let entry = self.file.entries.get(uri)?;
+        if entry.track.schema_version != SCHEMA_VERSION {
+            return None;
+        }
+        let age = Utc::now().timestamp().saturating_sub(entry.fetched_at);
+        if age < 0 || age as u64 >= self.ttl.as_secs() {
+            return None;
+        }
+        Some(entry.track.clone())
+    }
+
+    /// Stores `track` under its own ID, stamped with the current time.
+    pub fn insert(&mut self, track: TrackInspection) {
+        let entry = CacheEntry {
+            fetched_at: Utc::now().timestamp(),
+            track,
+        };
+        self.file.entries.insert(entry.track.id.clone(), entry);
+    }
+
+    /// Writes the cache atomically (temp file + rename), creating its directory.
+    /// Expired entries are dropped on the way out.
+    pub fn save(&mut self) -> std::io::Result<()> {
+        let now = Utc::now().timestamp();
+        let ttl = i64::try_from(self.ttl.as_secs()).unwrap_or(i64::MAX);
+        self.file
+            .entries
+            .retain(|_, entry| now.saturating_sub(entry.fetched_at) < ttl);
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_string(&self.file)?)?;
+        std::fs::rename(&tmp_path, &self.path)
+    }
+
+    /// Deletes the cache file at `path`. Returns `false` when there was nothing to delete.
+    pub fn clear(path: &Path) -> std::io::Result<bool> {
+        match std::fs::remove_file(path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::inspection;
+
+    fn temp_cache_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!(
+                "spotify-audit-test-{}-{}",
+                name,
+                std::process::id()
+            ))
+            .join(CACHE_FILE)
+    }
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let path = temp_cache_path("roundtrip");
+        let mut cache = TrackCache::open(&path, Duration::from_secs(3600));
+        assert!(cache.get("spotify:track:1").is_none());
+
+        cache.insert(inspection("spotify:track:1", "Track", "Artist"));
+        cache.save().unwrap();
+
+        let reopened = TrackCache::open(&path, Duration::from_secs(3600));
+        assert_eq!(reopened.get("spotify:track:1").unwrap().name, "Track");
+
+        assert!(TrackCache::clear(&path).unwrap());
+        assert!(!TrackCache::clear(&path).unwrap());
+        let _ = std::fs::remove_dir(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_cache_expired_entries_are_misses() {
+        let mut cache = TrackCache::open(temp_cache_path("ttl"), Duration::from_secs(60));
+        cache.insert(inspection("spotify:track:1", "Track", "Artist"));
+        assert!(cache.get("spotify:track:1").is_some());
+
+        cache
+            .file
+            .entries
+            .get_mut("spotify:track:1")
+            .unwrap()
+            .fetched_at -= 120;
+        assert!(cache.get("spotify:track:1").is_none());
+    }
+
+    #[test]
+    fn test_cache_ignores_other_schema_versions() {
+        let mut cache = TrackCache::open(temp_cache_path("schema"), Duration::from_secs(60));
+        let mut track = inspection("spotify:track:1", "Track", "Artist");
+        track.schema_version = String::new();
+        cache.insert(track);
+        assert!(cache.get("spotify:track:1").is_none());
+    }
+
+    #[test]
+    fn test_cache_ignores_corrupt_file() {
+        let path = temp_cache_path("corrupt");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "not json").unwrap();
+
+        let cache = TrackCache::open(&path, Duration::from_secs(60));
+        assert!(cache.file.entries.is_empty());
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_dir(path.parent().unwrap());
+    }
+}
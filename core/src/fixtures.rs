@@ -0,0 +1,64 @@
+/*
+    spotify-audit-rs | Rust CLI tool to audit playlists and sync Liked Songs.
+    Copyright (C) 2025  Israel Alberto Roldan Vega
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Fixtures shared by the unit tests of this crate.
+
+use crate::models::{ProblemKind, ProblematicTrack, TrackInspection, SCHEMA_VERSION};
+use std::collections::BTreeMap;
+
+/// A problematic track available in `markets` markets (not listed).
+pub(crate) fn problem(id: &str, markets: usize) -> ProblematicTrack {
+    ProblematicTrack {
+        id: id.to_string(),
+        name: format!("Track {}", id),
+        artists: "Artist".to_string(),
+        artist_names: vec!["Artist".to_string()],
+        album: "Album".to_string(),
+        reason: "Unplayable".to_string(),
+        kind: ProblemKind::from_availability(false, markets),
+        external_url: String::new(),
+        available_markets_count: markets,
+        available_markets: Vec::new(),
+        duration_ms: 0,
+    }
+}
+
+/// A lookup of the track `id` called `name` by `artist`, available in the US.
+pub(crate) fn inspection(id: &str, name: &str, artist: &str) -> TrackInspection {
+    TrackInspection {
+        schema_version: SCHEMA_VERSION.to_string(),
+        id: id.to_string(),
+        name: name.to_string(),
+        artists: vec![artist.to_string()],
+        album: "Album".to_string(),
+        release_date: "2020-01-01".to_string(),
+        duration_ms: 1000,
+        popularity: 0,
+        is_playable: None,
+        available_markets: vec!["US".to_string()],
+        external_ids: BTreeMap::new(),
+        external_urls: BTreeMap::new(),
+        disc_number: 1,
+        track_number: 1,
+        is_local: false,
+        linked_from: None,
+        audio_features: None,
+        my_market: None,
+        available_in_my_market: None,
+    }
+}
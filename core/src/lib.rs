@@ -18,9 +18,24 @@
 
 pub mod audit;
 pub mod auth;
+pub mod cache;
+#[cfg(test)]
+mod fixtures;
 pub mod models;
+pub mod output;
+pub mod util;
 
 // Re-export key items for convenience
-pub use audit::Auditor;
-pub use auth::get_spotify_client;
-pub use models::{AuditSummary, ProblematicTrack};
+pub use audit::{AuditError, Auditor, AuditorConfig, ProgressCallback, PLAYLIST_TRACK_LIMIT};
+pub use auth::{get_spotify_client, get_spotify_client_headless};
+pub use cache::TrackCache;
+pub use chrono::NaiveDate;
+pub use models::{
+    AlbumCompleteness, ArtistRef, AudioFeatures, AuditDiff, AuditStats, AuditSummary, BackupTrack,
+    DedupCandidate, DuplicateGroup, FollowedArtistsReport, IsrcCluster, IsrcOccurrence,
+    LibraryBackup, LibraryStats, MergeReport, MergeSource, PlaylistDetails, PlaylistDiff,
+    PlaylistFilter, PlaylistSortKey, PlaylistTrackCount, ProblemKind, ProblematicTrack,
+    ReplacementSuggestion, ReviewAction, ReviewLogEntry, SyncDirection, SyncReport, SyncState,
+    TrackSummary, UserProfile, SCHEMA_VERSION,
+};
+pub use rspotify::model::Market;
@@ -17,7 +17,49 @@
 */
 
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
+use std::path::Path;
+
+/// Version of the JSON report format, written into every report as `schema_version`.
+/// Bump it whenever a field of `AuditSummary`, `SyncReport` or `TrackInspection`
+/// is added, removed or changes meaning.
+pub const SCHEMA_VERSION: &str = "1.13";
+
+/// Why a track was flagged, for code that branches on the problem type.
+/// `ProblematicTrack::reason` keeps the human-readable text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemKind {
+    /// Unplayable for an unspecified reason (also reports written before kinds existed).
+    #[default]
+    Unplayable,
+    /// Unplayable and available in no market.
+    GloballyRemoved,
+    /// Unplayable here but available in at least one other market.
+    GeoLocked,
+    /// A local file that Spotify cannot play on this device.
+    LocalFileMissing,
+    /// The same track ID appears more than once in a playlist; it may play fine.
+    #[serde(alias = "duplicate_isrc")]
+    Duplicate,
+    /// Marked explicit; only reported by `explicit_only` scans.
+    Explicit,
+}
+
+impl ProblemKind {
+    /// Classifies an unplayable track by its availability.
+    pub fn from_availability(is_local: bool, available_markets_count: usize) -> Self {
+        if is_local {
+            ProblemKind::LocalFileMissing
+        } else if available_markets_count == 0 {
+            ProblemKind::GloballyRemoved
+        } else {
+            ProblemKind::GeoLocked
+        }
+    }
+}
 
 /// Represents a track that is found to be problematic (grey/unplayable).
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,36 +67,117 @@ pub struct ProblematicTrack {
     pub id: String,
     pub name: String,
     pub artists: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artist_names: Vec<String>, // One entry per artist; `artists` joins them with ", "
     pub album: String,
     pub reason: String, // Technical reason (e.g. "Track marked as unplayable")
+    #[serde(default)]
+    pub kind: ProblemKind,
     pub external_url: String,
     pub available_markets_count: usize, // How many markets have this track?
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub available_markets: Vec<String>, // Empty when the API response omitted the list
+    #[serde(default)]
+    pub duration_ms: u64,
 }
 
-impl fmt::Display for ProblematicTrack {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let status = if self.available_markets_count == 0 {
-            "🔴 REMOVED GLOBALLY".to_string()
+impl ProblematicTrack {
+    /// Replaces the market list (e.g. after a market-less lookup) and reclassifies
+    /// the availability-based kinds accordingly.
+    pub fn set_available_markets(&mut self, markets: Vec<String>) {
+        self.available_markets_count = markets.len();
+        self.available_markets = markets;
+        if matches!(
+            self.kind,
+            ProblemKind::Unplayable | ProblemKind::GloballyRemoved | ProblemKind::GeoLocked
+        ) {
+            self.kind = ProblemKind::from_availability(false, self.available_markets_count);
+        }
+    }
+
+    /// Human-readable availability status, e.g. "🔴 REMOVED GLOBALLY".
+    pub fn status(&self) -> String {
+        self.status_with_color(true)
+    }
+
+    /// `status`, with the emoji replaced by a bracketed ASCII tag (e.g.
+    /// "[REMOVED GLOBALLY]") unless `colored`.
+    pub fn status_with_color(&self, colored: bool) -> String {
+        let (marker, label) = match self.kind {
+            ProblemKind::Unplayable => ("⛔", "UNPLAYABLE"),
+            ProblemKind::GloballyRemoved => ("🔴", "REMOVED GLOBALLY"),
+            ProblemKind::GeoLocked => ("🌍", "GEO-LOCKED"),
+            ProblemKind::LocalFileMissing => ("💾", "LOCAL FILE MISSING"),
+            ProblemKind::Duplicate => ("🔁", "DUPLICATE"),
+            ProblemKind::Explicit => ("🔞", "EXPLICIT"),
+        };
+        let status = if colored {
+            format!("{} {}", marker, label)
         } else {
+            format!("[{}]", label)
+        };
+        if self.kind == ProblemKind::GeoLocked {
             format!(
-                "🌍 GEO-LOCKED (Available in {} markets)",
-                self.available_markets_count
+                "{} (Available in {} markets)",
+                status, self.available_markets_count
             )
-        };
+        } else {
+            status
+        }
+    }
 
-        write!(
-            f,
+    /// The `Display` line, for output where emoji may not render (pipes, files).
+    pub fn format_with_color(&self, colored: bool) -> String {
+        format!(
             "[{}] {} - {} (Album: {}) -> {} | {}",
-            self.id, self.name, self.artists, self.album, self.reason, status
+            self.id,
+            self.name,
+            self.artists,
+            self.album,
+            self.reason,
+            self.status_with_color(colored)
         )
     }
 }
 
+impl fmt::Display for ProblematicTrack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format_with_color(true))
+    }
+}
+
 /// Summary of a library scan.
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AuditSummary {
+    #[serde(default)]
+    pub schema_version: String, // Empty in reports written before versioning
     pub total_tracks_scanned: u32,
+    #[serde(default)]
+    pub total_duration_ms: u64, // Listening time of every scanned track
     pub problematic_tracks: Vec<ProblematicTrack>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scan_error: Option<String>, // Set when the scan aborted part-way (e.g. in scan_all_playlists)
+    #[serde(default)]
+    pub truncated: bool, // Set when a scan limit stopped the scan before the last track
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scan_window_start: Option<String>, // YYYY-MM-DD of a `since` scan: older saves were skipped
+    #[serde(default)]
+    pub explicit_count: u32, // Scanned tracks marked explicit
+}
+
+impl Default for AuditSummary {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION.to_string(),
+            total_tracks_scanned: 0,
+            total_duration_ms: 0,
+            problematic_tracks: Vec::new(),
+            scan_error: None,
+            truncated: false,
+            scan_window_start: None,
+            explicit_count: 0,
+        }
+    }
 }
 
 impl AuditSummary {
@@ -62,9 +185,199 @@ impl AuditSummary {
         Self::default()
     }
 
+    /// IDs of the problematic tracks, for membership tests.
+    pub fn problematic_track_ids_set(&self) -> HashSet<&str> {
+        self.problematic_tracks
+            .iter()
+            .map(|track| track.id.as_str())
+            .collect()
+    }
+
+    /// Whether `limit` tracks (if any limit) were scanned already.
+    pub fn reached(&self, limit: Option<usize>) -> bool {
+        limit.is_some_and(|limit| self.total_tracks_scanned as usize >= limit)
+    }
+
     pub fn add_problem(&mut self, track: ProblematicTrack) {
         self.problematic_tracks.push(track);
     }
+
+    /// Listening time taken up by the problematic tracks.
+    pub fn problematic_duration_ms(&self) -> u64 {
+        self.problematic_tracks
+            .iter()
+            .map(|track| track.duration_ms)
+            .sum()
+    }
+
+    /// Combines independent scans into one report: scanned, explicit and duration
+    /// totals are summed and problematic tracks concatenated, keeping the first entry
+    /// for each track ID.
+    /// Scan errors are kept, joined with "; ". A scan window is kept only when every
+    /// report shares it.
+    pub fn merge(summaries: impl IntoIterator<Item = AuditSummary>) -> AuditSummary {
+        let mut merged = AuditSummary::new();
+        let mut seen = HashSet::new();
+        let mut errors = Vec::new();
+        let mut windows = HashSet::new();
+
+        for summary in summaries {
+            merged.total_tracks_scanned += summary.total_tracks_scanned;
+            merged.total_duration_ms += summary.total_duration_ms;
+            merged.explicit_count += summary.explicit_count;
+            merged.truncated |= summary.truncated;
+            for track in summary.problematic_tracks {
+                if seen.insert(track.id.clone()) {
+                    merged.add_problem(track);
+                }
+            }
+            errors.extend(summary.scan_error);
+            windows.insert(summary.scan_window_start);
+        }
+        if windows.len() == 1 {
+            merged.scan_window_start = windows.into_iter().next().flatten();
+        }
+
+        if !errors.is_empty() {
+            merged.scan_error = Some(errors.join("; "));
+        }
+        merged
+    }
+
+    /// Returns a copy that only keeps the tracks removed globally (no market left).
+    pub fn filter_globally_removed(&self) -> AuditSummary {
+        AuditSummary {
+            schema_version: self.schema_version.clone(),
+            total_tracks_scanned: self.total_tracks_scanned,
+            total_duration_ms: self.total_duration_ms,
+            problematic_tracks: self
+                .problematic_tracks
+                .iter()
+                .filter(|track| track.kind == ProblemKind::GloballyRemoved)
+                .cloned()
+                .collect(),
+            scan_error: self.scan_error.clone(),
+            truncated: self.truncated,
+            scan_window_start: self.scan_window_start.clone(),
+            explicit_count: self.explicit_count,
+        }
+    }
+
+    /// Returns a copy that only keeps the geo-locked tracks (available in some market).
+    pub fn filter_geo_locked(&self) -> AuditSummary {
+        AuditSummary {
+            schema_version: self.schema_version.clone(),
+            total_tracks_scanned: self.total_tracks_scanned,
+            total_duration_ms: self.total_duration_ms,
+            problematic_tracks: self
+                .problematic_tracks
+                .iter()
+                .filter(|track| track.kind == ProblemKind::GeoLocked)
+                .cloned()
+                .collect(),
+            scan_error: self.scan_error.clone(),
+            truncated: self.truncated,
+            scan_window_start: self.scan_window_start.clone(),
+            explicit_count: self.explicit_count,
+        }
+    }
+
+    /// Groups the problematic tracks by artist, in report order. A track with several
+    /// artists is listed under each of them. Reports written before `artist_names`
+    /// existed only have the joined `artists`, which is then taken as one artist.
+    pub fn group_by_artist(&self) -> HashMap<String, Vec<&ProblematicTrack>> {
+        let mut groups: HashMap<String, Vec<&ProblematicTrack>> = HashMap::new();
+        for track in &self.problematic_tracks {
+            let names: Vec<&str> = if track.artist_names.is_empty() {
+                vec![track.artists.as_str()]
+            } else {
+                track.artist_names.iter().map(String::as_str).collect()
+            };
+            for artist in names.into_iter().filter(|a| !a.is_empty()) {
+                groups.entry(artist.to_string()).or_default().push(track);
+            }
+        }
+        groups
+    }
+
+    /// Breaks the problematic tracks down by kind and by the markets they remain in.
+    pub fn stats(&self) -> AuditStats {
+        let mut stats = AuditStats {
+            total_problematic: self.problematic_tracks.len() as u32,
+            ..Default::default()
+        };
+
+        for track in &self.problematic_tracks {
+            match track.kind {
+                ProblemKind::GloballyRemoved => stats.globally_removed += 1,
+                ProblemKind::GeoLocked => stats.geo_locked += 1,
+                ProblemKind::Duplicate => stats.duplicates += 1,
+                ProblemKind::Explicit => stats.explicit += 1,
+                _ => stats.other += 1,
+            }
+            for market in &track.available_markets {
+                *stats.markets_histogram.entry(market.clone()).or_default() += 1;
+            }
+        }
+
+        if self.total_tracks_scanned > 0 {
+            stats.percent_problematic =
+                f64::from(stats.total_problematic) * 100.0 / f64::from(self.total_tracks_scanned);
+        }
+        stats
+    }
+}
+
+/// Aggregate figures for an `AuditSummary`, see `AuditSummary::stats`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuditStats {
+    pub globally_removed: u32,
+    pub geo_locked: u32,
+    pub total_problematic: u32,
+    #[serde(default)]
+    pub duplicates: u32, // Playlist duplicates, counted in neither availability bucket
+    #[serde(default)]
+    pub explicit: u32, // Explicit tracks of an `--explicit-only` scan
+    #[serde(default)]
+    pub other: u32, // Local files and tracks whose availability is unknown
+    pub percent_problematic: f64, // Share of the scanned tracks, 0-100
+    pub markets_histogram: BTreeMap<String, u32>, // Country -> problematic tracks still available there
+}
+
+/// High-level overview of 'Liked Songs', see `Auditor::compute_library_stats`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LibraryStats {
+    pub total_tracks: u32,
+    pub unplayable: u32,   // Unplayable in the user's market
+    pub zero_markets: u32, // Unplayable everywhere (removed globally)
+    pub unique_artists: u32,
+    pub unique_albums: u32,
+    pub local_files: u32,
+    #[serde(default)]
+    pub total_duration_ms: u64,
+    pub decades: BTreeMap<String, u32>, // e.g. "1990s" -> tracks released in that decade
+}
+
+impl LibraryStats {
+    /// Counts a release date (`YYYY`, `YYYY-MM` or `YYYY-MM-DD`) into its decade bucket.
+    /// Dates without a readable year (including Spotify's "0000" placeholder) are ignored.
+    pub fn add_release_date(&mut self, release_date: &str) {
+        let year = release_date
+            .get(..4)
+            .and_then(|year| year.parse::<u32>().ok())
+            .filter(|year| *year > 0);
+        if let Some(year) = year {
+            let decade = format!("{}s", year - year % 10);
+            *self.decades.entry(decade).or_default() += 1;
+        }
+    }
+}
+
+/// Delta between two audits of the same library or playlist.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AuditDiff {
+    pub fixed: Vec<ProblematicTrack>, // Problematic before, not anymore
+    pub regressed: Vec<ProblematicTrack>, // Newly problematic
 }
 
 /// Detailed log for a sync operation batch.
@@ -76,17 +389,107 @@ pub struct SyncBatchLog {
     pub status: String, // "Success" or error message
 }
 
+/// Which way a sync copies tracks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncDirection {
+    /// `Auditor::sync_playlist_to_liked`.
+    #[default]
+    PlaylistToLiked,
+    /// `Auditor::sync_liked_to_playlist`.
+    LikedToPlaylist,
+}
+
 /// Report for the sync operation.
-#[derive(Debug, Default, Serialize, Deserialize)]
+///
+/// The field names follow the playlist-to-Liked-Songs flow. In a `LikedToPlaylist`
+/// report, the `*_liked_count` fields count the target playlist, `total_tracks_in_playlist`
+/// counts 'Liked Songs' and `skipped_already_liked` counts tracks the playlist had.
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SyncReport {
+    #[serde(default)]
+    pub schema_version: String, // Empty in reports written before versioning
+    #[serde(default)]
+    pub direction: SyncDirection,
     pub initial_liked_count: u32,
     pub final_liked_count: u32,
     pub total_tracks_in_playlist: u32,
     pub tracks_processed: u32,
-    pub estimated_added: u32, // final - initial
+    pub skipped_already_liked: u32,
+    /// Tracks that were not liked before the run, i.e. the source IDs minus the liked
+    /// IDs, and whose batch was accepted. This is what the run adds (or would add, on
+    /// a dry run).
+    pub estimated_added: u32,
+    /// Liked Songs count after the run minus the count before it (0 on a dry run).
+    /// Cross-checks `estimated_added`; other devices liking tracks meanwhile skew it.
+    #[serde(default)]
+    pub confirmed_added: u32,
+    pub dry_run: bool,
+    #[serde(default)]
+    pub batch_delay_ms: u64, // Pause between add batches
+    #[serde(default)]
+    pub skipped_tracks: Vec<String>, // IDs that were already liked
+    #[serde(default)]
+    pub added_tracks: Vec<String>, // IDs whose batch was accepted (empty on a dry run)
+    pub failed_tracks: Vec<String>, // IDs that could not be parsed or whose batch was rejected
     pub batch_logs: Vec<SyncBatchLog>,
 }
 
+impl Default for SyncReport {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION.to_string(),
+            direction: SyncDirection::PlaylistToLiked,
+            initial_liked_count: 0,
+            final_liked_count: 0,
+            total_tracks_in_playlist: 0,
+            tracks_processed: 0,
+            skipped_already_liked: 0,
+            estimated_added: 0,
+            confirmed_added: 0,
+            dry_run: false,
+            batch_delay_ms: 0,
+            skipped_tracks: Vec::new(),
+            added_tracks: Vec::new(),
+            failed_tracks: Vec::new(),
+            batch_logs: Vec::new(),
+        }
+    }
+}
+
+/// Batches a sync has already added, checkpointed after each one so an interrupted
+/// sync can resume where it stopped (see `Auditor::with_sync_state`).
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SyncState {
+    pub playlist_id: String,
+    pub completed_batches: Vec<SyncBatchLog>,
+}
+
+impl SyncState {
+    /// Reads the state at `path`. Returns `None` when there is no state file.
+    pub fn load(path: &Path) -> std::io::Result<Option<SyncState>> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Writes the state atomically (temp file + rename).
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&tmp_path, path)
+    }
+}
+
+/// Track total of a playlist, see `Auditor::playlist_track_count`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlaylistTrackCount {
+    pub playlist_id: String,
+    pub tracks: u32,
+}
+
 /// Summary of a playlist for listing purposes.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PlaylistSummary {
@@ -96,11 +499,111 @@ pub struct PlaylistSummary {
     pub is_public: bool,
     pub is_collaborative: bool,
     pub owner_name: String,
+    #[serde(default)]
+    pub owner_id: String, // Bare user ID, unlike `owner_name` it is never a display name
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
-/// Detailed forensic information about a single track.
+impl PlaylistSummary {
+    /// Compares two playlists in the order `key` lists them.
+    pub fn cmp_by(&self, other: &Self, key: PlaylistSortKey) -> Ordering {
+        let lower = |pl: &Self| (pl.owner_name.to_lowercase(), pl.name.to_lowercase());
+        match key {
+            PlaylistSortKey::Name => self.name.to_lowercase().cmp(&other.name.to_lowercase()),
+            PlaylistSortKey::TrackCount => other.total_tracks.cmp(&self.total_tracks),
+            PlaylistSortKey::Owner => lower(self).cmp(&lower(other)),
+        }
+    }
+}
+
+/// Sort orders for `Auditor::list_playlists_sorted`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistSortKey {
+    /// Alphabetical, ignoring case.
+    Name,
+    /// Largest playlists first.
+    TrackCount,
+    /// Alphabetical by owner name, then by playlist name.
+    Owner,
+}
+
+/// Which playlists `Auditor::list_playlists_sorted` keeps; every flag narrows the list.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlaylistFilter {
+    pub owned_only: bool,
+    pub collaborative_only: bool,
+    pub public_only: bool,
+}
+
+/// A playlist summary plus the fields only returned when fetching the full playlist.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlaylistDetails {
+    #[serde(flatten)]
+    pub summary: PlaylistSummary,
+    pub snapshot_id: String,
+    pub image_url: Option<String>,
+    pub followers: u32,
+}
+
+/// Lightweight track reference used in comparisons.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackSummary {
+    pub id: String,
+    pub name: String,
+    pub artists: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isrc: Option<String>,
+}
+
+impl fmt::Display for TrackSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {} - {}",
+            self.id,
+            self.name,
+            self.artists.join(", ")
+        )
+    }
+}
+
+/// Track overlap between two track lists (two playlists, or a playlist and
+/// 'Liked Songs'), see `Auditor::diff_track_lists`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PlaylistDiff {
+    pub only_in_a: Vec<TrackSummary>,
+    pub only_in_b: Vec<TrackSummary>,
+    pub in_both: Vec<TrackSummary>,
+}
+
+/// A saved track as exported by a backup. Carries enough data to re-add it later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupTrack {
+    pub id: String,
+    pub name: String,
+    pub artists: Vec<String>,
+    pub album: String,
+    pub isrc: Option<String>,
+    pub added_at: String, // RFC 3339 timestamp of when the track was liked
+    pub available_markets: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_playable: Option<bool>, // Only reported when the backup was taken for a market
+}
+
+/// Self-describing 'Liked Songs' backup file, as written by `Auditor::backup_liked_songs`.
 #[derive(Debug, Serialize, Deserialize)]
+pub struct LibraryBackup {
+    pub saved_at: String, // RFC 3339
+    pub user_id: String,
+    pub tracks: Vec<BackupTrack>,
+}
+
+/// Detailed forensic information about a single track.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackInspection {
+    #[serde(default)]
+    pub schema_version: String, // Empty in output written before versioning
     pub id: String,
     pub name: String,
     pub artists: Vec<String>,
@@ -110,16 +613,195 @@ pub struct TrackInspection {
     pub popularity: u32,
     pub is_playable: Option<bool>,
     pub available_markets: Vec<String>,
-    pub external_ids: std::collections::HashMap<String, String>, // ISRC, EAN, UPC
-    pub external_urls: std::collections::HashMap<String, String>,
+    pub external_ids: BTreeMap<String, String>, // ISRC, EAN, UPC; sorted for stable output
+    pub external_urls: BTreeMap<String, String>,
     pub disc_number: i32,
     pub track_number: u32,
     pub is_local: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub linked_from: Option<String>, // URI of the requested track when Spotify relinked it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_features: Option<AudioFeatures>, // Only filled by `inspect_track_with_features`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub my_market: Option<String>, // Market of the lookup, or the user's country
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub available_in_my_market: Option<bool>, // None when `my_market` is unknown
+}
+
+impl TrackInspection {
+    /// Records `code` as the user's market along with whether the track is available
+    /// there. Lookups made for a market omit the market list, so `is_playable` decides.
+    pub fn set_my_market(&mut self, code: Option<&str>) {
+        self.available_in_my_market = code.map(|code| match self.is_playable {
+            Some(playable) if self.available_markets.is_empty() => playable,
+            _ => self.available_markets.iter().any(|market| market == code),
+        });
+        self.my_market = code.map(str::to_string);
+    }
+}
+
+/// Spotify's audio analysis summary of a track, see `Auditor::inspect_track_with_features`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioFeatures {
+    pub tempo: f32, // Beats per minute
+    pub key: i32,   // Pitch class (0 = C, 1 = C#/Db, ...), -1 when not detected
+    pub mode: i32,  // 1 = major, 0 = minor, -1 when not detected
+    pub time_signature: i32,
+    pub energy: f32, // 0.0 - 1.0, like the other ratios below
+    pub danceability: f32,
+    pub valence: f32,
+    pub acousticness: f32,
+    pub instrumentalness: f32,
+    pub liveness: f32,
+    pub speechiness: f32,
+    pub loudness: f32, // Average dB, typically -60 - 0
+}
+
+/// Liked tracks that look like the same song: same artists and (nearly) the same name,
+/// but different track IDs. See `Auditor::find_duplicate_tracks_by_name`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub artists: String, // Normalised (lowercase) artist key shared by the group
+    pub name: String,    // Normalised name of the first track in the group
+    pub tracks: Vec<TrackInspection>,
+}
+
+/// One occurrence of a recording in a playlist, see `IsrcCluster`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IsrcOccurrence {
+    pub playlist_id: String,
+    pub playlist_name: String,
+    pub track_id: String,
+    pub track_name: String,
+}
+
+/// A recording (ISRC) found in several playlists under different track IDs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IsrcCluster {
+    pub isrc: String,
+    pub occurrences: Vec<IsrcOccurrence>,
+}
+
+/// A liked track removed everywhere, and the playable release of the same recording
+/// (ISRC) that search found for it, if any. See `Auditor::suggest_isrc_replacements`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplacementSuggestion {
+    pub dead: TrackSummary,
+    pub replacement: Option<TrackInspection>,
+}
+
+/// The authenticated account. See `Auditor::get_user_profile`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserProfile {
+    pub id: String,
+    pub display_name: Option<String>,
+    pub email: Option<String>, // Only returned with the user-read-email scope
+    pub country: Option<String>, // ISO code, e.g. "US"
+    pub product: Option<String>, // "premium" or "free"
+    pub followers: u32,
+}
+
+/// An album of which 'Liked Songs' hold only some tracks, see
+/// `Auditor::audit_album_completeness`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlbumCompleteness {
+    pub album_id: String,
+    pub name: String,
+    pub artists: String,
+    pub total_tracks: u32,
+    pub saved_tracks: u32,
+    pub missing_tracks: Vec<(i32, u32)>, // (disc, track) numbers not saved, in album order
+}
+
+impl AlbumCompleteness {
+    /// The missing track numbers, e.g. "3, 4", prefixed with the disc ("2-3") when the
+    /// album has several discs.
+    pub fn missing_label(&self) -> String {
+        let multi_disc = self.missing_tracks.iter().any(|(disc, _)| *disc != 1);
+        self.missing_tracks
+            .iter()
+            .map(|(disc, track)| {
+                if multi_disc {
+                    format!("{}-{}", disc, track)
+                } else {
+                    track.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// An artist with its number of 'Liked Songs', see `Auditor::list_liked_artists`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtistRef {
+    pub id: String,
+    pub name: String,
+    pub saved_tracks: u32, // Liked Songs crediting the artist
+}
+
+/// Where the followed artists and the artists of 'Liked Songs' disagree, see
+/// `Auditor::compare_followed_artists`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FollowedArtistsReport {
+    pub followed_total: u32,
+    pub followed_without_saves: Vec<ArtistRef>, // By name
+    pub saved_not_followed: Vec<ArtistRef>,     // Most saved tracks first
+}
+
+/// Result of `Auditor::merge_playlists`, with one entry per source in the order given.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MergeReport {
+    pub destination_id: String,
+    pub dedup: bool,
+    pub sources: Vec<MergeSource>,
+    pub total_added: u32,
+    pub total_skipped: u32, // Only non-zero with `dedup`
+}
+
+/// What one source playlist contributed to a merge. With `dedup`, `skipped` counts its
+/// tracks that were already in the destination or came from an earlier source.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MergeSource {
+    pub playlist_id: String,
+    pub added: u32,
+    pub skipped: u32,
+}
+
+/// A track that a dedup plan would remove. `id` is the track URI; `label` names the
+/// track and its market count for display.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DedupCandidate {
+    pub id: String,
+    pub label: String,
+}
+
+/// What the user chose for one problematic track during `scan --interactive`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewAction {
+    Skip,
+    Remove,
+    FindReplacement,
+}
+
+/// One entry of the `scan --interactive` session log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewLogEntry {
+    pub track_id: String,
+    pub name: String,
+    pub artists: String,
+    pub action: ReviewAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<TrackInspection>, // Set when a replacement was found
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>, // Set when the action failed
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::problem;
 
     #[test]
     fn test_problematic_track_display_global_removed() {
@@ -127,10 +809,14 @@ mod tests {
             id: "123".to_string(),
             name: "Ghost Track".to_string(),
             artists: "Unknown Artist".to_string(),
+            artist_names: vec!["Unknown Artist".to_string()],
             album: "Lost Album".to_string(),
             reason: "Unplayable".to_string(),
+            kind: ProblemKind::GloballyRemoved,
             external_url: "http://...".to_string(),
             available_markets_count: 0,
+            available_markets: Vec::new(),
+            duration_ms: 0,
         };
 
         let display = format!("{}", track);
@@ -144,10 +830,14 @@ mod tests {
             id: "456".to_string(),
             name: "Locked Song".to_string(),
             artists: "Famous Singer".to_string(),
+            artist_names: vec!["Famous Singer".to_string()],
             album: "Region Album".to_string(),
             reason: "Unplayable".to_string(),
+            kind: ProblemKind::GeoLocked,
             external_url: "http://...".to_string(),
             available_markets_count: 5,
+            available_markets: Vec::new(),
+            duration_ms: 0,
         };
 
         let display = format!("{}", track);
@@ -155,27 +845,397 @@ mod tests {
         assert!(display.contains("Available in 5 markets"));
     }
 
+    #[test]
+    fn test_problematic_track_format_without_color() {
+        let track = problem("456", 5);
+        let plain = track.format_with_color(false);
+        assert!(plain.ends_with("| [GEO-LOCKED] (Available in 5 markets)"));
+        assert!(plain.is_ascii());
+        assert_eq!(track.format_with_color(true), track.to_string());
+
+        assert!(problem("456", 0)
+            .format_with_color(false)
+            .ends_with("| [REMOVED GLOBALLY]"));
+    }
+
+    #[test]
+    fn test_status_follows_kind() {
+        let unknown = ProblematicTrack {
+            kind: ProblemKind::Unplayable,
+            ..problem("1", 0)
+        };
+        assert_eq!(unknown.status_with_color(false), "[UNPLAYABLE]");
+        let local = ProblematicTrack {
+            kind: ProblemKind::LocalFileMissing,
+            ..problem("2", 0)
+        };
+        assert_eq!(local.status_with_color(false), "[LOCAL FILE MISSING]");
+    }
+
+    #[test]
+    fn test_duplicate_is_neither_removed_nor_geo_locked() {
+        let duplicate = ProblematicTrack {
+            kind: ProblemKind::Duplicate,
+            ..problem("dup", 0)
+        };
+        assert_eq!(duplicate.status_with_color(false), "[DUPLICATE]");
+        let old: ProblemKind = serde_json::from_str(r#""duplicate_isrc""#).unwrap();
+        assert_eq!(old, ProblemKind::Duplicate);
+
+        let mut summary = AuditSummary::new();
+        summary.add_problem(problem("dead", 0));
+        summary.add_problem(duplicate);
+        let stats = summary.stats();
+        assert_eq!(stats.globally_removed, 1);
+        assert_eq!(stats.geo_locked, 0);
+        assert_eq!(stats.duplicates, 1);
+        assert_eq!(stats.total_problematic, 2);
+        assert_eq!(
+            summary.filter_globally_removed().problematic_tracks.len(),
+            1
+        );
+        assert!(summary.filter_geo_locked().problematic_tracks.is_empty());
+    }
+
+    #[test]
+    fn test_problem_kind() {
+        assert_eq!(
+            ProblemKind::from_availability(true, 0),
+            ProblemKind::LocalFileMissing
+        );
+        assert_eq!(
+            ProblemKind::from_availability(false, 0),
+            ProblemKind::GloballyRemoved
+        );
+        assert_eq!(
+            ProblemKind::from_availability(false, 3),
+            ProblemKind::GeoLocked
+        );
+        assert_eq!(
+            serde_json::to_value(ProblemKind::GloballyRemoved).unwrap(),
+            "globally_removed"
+        );
+
+        // Reports written before kinds existed still load.
+        let old: ProblematicTrack = serde_json::from_str(
+            r#"{"id":"1","name":"A","artists":"B","album":"C","reason":"D",
+                "external_url":"","available_markets_count":0}"#,
+        )
+        .unwrap();
+        assert_eq!(old.kind, ProblemKind::Unplayable);
+
+        let mut track = old.clone();
+        track.set_available_markets(vec!["MX".to_string()]);
+        assert_eq!(track.kind, ProblemKind::GeoLocked);
+        assert_eq!(track.available_markets_count, 1);
+    }
+
     #[test]
     fn test_audit_summary_aggregation() {
         let mut summary = AuditSummary::new();
         assert_eq!(summary.total_tracks_scanned, 0);
         assert!(summary.problematic_tracks.is_empty());
 
-        let track = ProblematicTrack {
-            id: "1".to_string(),
-            name: "A".to_string(),
-            artists: "B".to_string(),
-            album: "C".to_string(),
-            reason: "D".to_string(),
-            external_url: "E".to_string(),
-            available_markets_count: 0,
-        };
-
-        summary.add_problem(track);
+        summary.add_problem(problem("1", 0));
         summary.total_tracks_scanned += 1;
 
         assert_eq!(summary.total_tracks_scanned, 1);
         assert_eq!(summary.problematic_tracks.len(), 1);
-        assert_eq!(summary.problematic_tracks[0].name, "A");
+        assert_eq!(summary.problematic_tracks[0].name, "Track 1");
+    }
+
+    #[test]
+    fn test_audit_summary_stats() {
+        let mut summary = AuditSummary::new();
+        summary.total_tracks_scanned = 8;
+        for (markets, available) in [(0, vec![]), (2, vec!["US", "MX"]), (1, vec!["US"])] {
+            summary.add_problem(ProblematicTrack {
+                available_markets: available.into_iter().map(String::from).collect(),
+                ..problem("1", markets)
+            });
+        }
+        // A market scan that could not look up the markets: not counted as removed.
+        summary.add_problem(ProblematicTrack {
+            kind: ProblemKind::Unplayable,
+            ..problem("2", 0)
+        });
+        summary.add_problem(ProblematicTrack {
+            kind: ProblemKind::Explicit,
+            ..problem("3", 0)
+        });
+
+        let stats = summary.stats();
+        assert_eq!(stats.globally_removed, 1);
+        assert_eq!(stats.geo_locked, 2);
+        assert_eq!(stats.explicit, 1);
+        assert_eq!(stats.other, 1);
+        assert_eq!(stats.total_problematic, 5);
+        assert_eq!(stats.percent_problematic, 62.5);
+        assert_eq!(stats.markets_histogram.get("US"), Some(&2));
+        assert_eq!(stats.markets_histogram.get("MX"), Some(&1));
+
+        assert_eq!(AuditSummary::new().stats().percent_problematic, 0.0);
+    }
+
+    #[test]
+    fn test_filter_globally_removed() {
+        let mut summary = AuditSummary::new();
+        summary.total_tracks_scanned = 3;
+        for (id, markets) in [("dead", 0), ("locked", 4)] {
+            summary.add_problem(problem(id, markets));
+        }
+
+        // Availability not looked up yet: in neither category.
+        summary.add_problem(ProblematicTrack {
+            kind: ProblemKind::Unplayable,
+            ..problem("unknown", 0)
+        });
+
+        let removed = summary.filter_globally_removed();
+        assert_eq!(removed.total_tracks_scanned, 3);
+        assert_eq!(removed.problematic_tracks.len(), 1);
+        assert_eq!(removed.problematic_tracks[0].id, "dead");
+        assert_eq!(summary.problematic_tracks.len(), 3);
+
+        let locked = summary.filter_geo_locked();
+        assert_eq!(locked.total_tracks_scanned, 3);
+        assert_eq!(locked.problematic_tracks.len(), 1);
+        assert_eq!(locked.problematic_tracks[0].id, "locked");
+    }
+
+    #[test]
+    fn test_audit_summary_merge() {
+        let report = |ids: &[&str], scanned: u32, error: Option<&str>| {
+            let mut summary = AuditSummary::new();
+            summary.total_tracks_scanned = scanned;
+            summary.scan_error = error.map(String::from);
+            for id in ids {
+                summary.add_problem(problem(id, 0));
+            }
+            summary
+        };
+
+        let merged = AuditSummary::merge(vec![
+            report(&["1", "2"], 10, None),
+            report(&["2", "3"], 5, Some("timeout")),
+            report(&[], 0, Some("forbidden")),
+        ]);
+
+        assert_eq!(merged.total_tracks_scanned, 15);
+        let ids: Vec<&str> = merged
+            .problematic_tracks
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["1", "2", "3"]);
+        assert_eq!(merged.scan_error.as_deref(), Some("timeout; forbidden"));
+
+        let empty = AuditSummary::merge(Vec::new());
+        assert_eq!(empty.total_tracks_scanned, 0);
+        assert!(empty.scan_error.is_none());
+    }
+
+    #[test]
+    fn test_library_stats_decades() {
+        let mut stats = LibraryStats::default();
+        stats.add_release_date("1994-05-01");
+        stats.add_release_date("1999");
+        stats.add_release_date("2001-03");
+        stats.add_release_date("0000");
+        stats.add_release_date("");
+
+        assert_eq!(stats.decades.get("1990s"), Some(&2));
+        assert_eq!(stats.decades.get("2000s"), Some(&1));
+        assert_eq!(stats.decades.len(), 2);
+    }
+
+    #[test]
+    fn test_audit_summary_scan_error_serialization() {
+        let clean = serde_json::to_string(&AuditSummary::new()).unwrap();
+        assert!(!clean.contains("scan_error"));
+
+        let failed = AuditSummary {
+            scan_error: Some("boom".to_string()),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&failed).unwrap();
+        assert!(json.contains("\"scan_error\":\"boom\""));
+    }
+
+    #[test]
+    fn test_merge_keeps_shared_scan_window_only() {
+        let window = |start: Option<&str>| AuditSummary {
+            scan_window_start: start.map(str::to_string),
+            ..Default::default()
+        };
+        let same = AuditSummary::merge([window(Some("2025-01-01")), window(Some("2025-01-01"))]);
+        assert_eq!(same.scan_window_start.as_deref(), Some("2025-01-01"));
+
+        let mixed = AuditSummary::merge([window(Some("2025-01-01")), window(None)]);
+        assert_eq!(mixed.scan_window_start, None);
+    }
+
+    #[test]
+    fn test_album_completeness_missing_label() {
+        let mut album = AlbumCompleteness {
+            album_id: "1".to_string(),
+            name: "Album".to_string(),
+            artists: "Artist".to_string(),
+            total_tracks: 10,
+            saved_tracks: 8,
+            missing_tracks: vec![(1, 3), (1, 9)],
+        };
+        assert_eq!(album.missing_label(), "3, 9");
+
+        album.missing_tracks.push((2, 1));
+        assert_eq!(album.missing_label(), "1-3, 1-9, 2-1");
+    }
+
+    #[test]
+    fn test_problematic_track_ids_set_ignores_repeats() {
+        let track = problem("spotify:track:1", 0);
+        let mut summary = AuditSummary::new();
+        summary.add_problem(track.clone());
+        summary.add_problem(track);
+
+        let ids = summary.problematic_track_ids_set();
+        assert_eq!(ids.len(), 1);
+        assert!(ids.contains("spotify:track:1"));
+    }
+
+    #[test]
+    fn test_audit_summary_reached_limit() {
+        let summary = AuditSummary {
+            total_tracks_scanned: 2,
+            ..Default::default()
+        };
+        assert!(!summary.reached(None));
+        assert!(!summary.reached(Some(3)));
+        assert!(summary.reached(Some(2)));
+        assert!(!summary.truncated);
+    }
+
+    #[test]
+    fn test_review_log_entry_serialization() {
+        let entry = ReviewLogEntry {
+            track_id: "spotify:track:1".to_string(),
+            name: "Song".to_string(),
+            artists: "Artist".to_string(),
+            action: ReviewAction::FindReplacement,
+            replacement: None,
+            error: None,
+        };
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["action"], "find_replacement");
+        assert!(json.get("replacement").is_none());
+        assert!(json.get("error").is_none());
+    }
+
+    #[test]
+    fn test_playlist_summary_cmp_by() {
+        let playlist = |name: &str, total_tracks: u32, owner_name: &str| PlaylistSummary {
+            id: String::new(),
+            name: name.to_string(),
+            total_tracks,
+            is_public: true,
+            is_collaborative: false,
+            owner_name: owner_name.to_string(),
+            owner_id: String::new(),
+            description: None,
+        };
+        let small = playlist("beta", 5, "Zoe");
+        let large = playlist("Alpha", 50, "zoe");
+
+        assert_eq!(
+            small.cmp_by(&large, PlaylistSortKey::Name),
+            Ordering::Greater
+        );
+        assert_eq!(
+            small.cmp_by(&large, PlaylistSortKey::TrackCount),
+            Ordering::Greater
+        );
+        assert_eq!(
+            large.cmp_by(&small, PlaylistSortKey::TrackCount),
+            Ordering::Less
+        );
+        // Same owner (ignoring case), so the playlist name decides.
+        assert_eq!(
+            small.cmp_by(&large, PlaylistSortKey::Owner),
+            Ordering::Greater
+        );
+        assert_eq!(
+            small.cmp_by(&small, PlaylistSortKey::Owner),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_schema_version() {
+        let json = serde_json::to_value(AuditSummary::new()).unwrap();
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        let json = serde_json::to_value(SyncReport::default()).unwrap();
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+
+        // Reports written before versioning still load, with an empty version.
+        let old: AuditSummary =
+            serde_json::from_str(r#"{"total_tracks_scanned":1,"problematic_tracks":[]}"#).unwrap();
+        assert_eq!(old.schema_version, "");
+        assert_eq!(old.filter_geo_locked().schema_version, old.schema_version);
+    }
+
+    #[test]
+    fn test_sync_state_roundtrip() {
+        let path = std::env::temp_dir().join(format!(
+            "spotify-audit-test-sync-state-{}.json",
+            std::process::id()
+        ));
+        assert!(SyncState::load(&path).unwrap().is_none());
+
+        let state = SyncState {
+            playlist_id: "abc".to_string(),
+            completed_batches: vec![SyncBatchLog {
+                batch_index: 2,
+                tracks_count: 1,
+                track_ids: vec!["spotify:track:1".to_string()],
+                status: "Success".to_string(),
+            }],
+        };
+        state.save(&path).unwrap();
+
+        let loaded = SyncState::load(&path).unwrap().unwrap();
+        assert_eq!(loaded.playlist_id, "abc");
+        assert_eq!(loaded.completed_batches[0].batch_index, 2);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_group_by_artist() {
+        let mut summary = AuditSummary::new();
+        for (id, artists) in [
+            ("1", &["Queen"][..]),
+            ("2", &["Queen", "David Bowie"]),
+            ("3", &["Tyler, The Creator"]),
+        ] {
+            summary.add_problem(ProblematicTrack {
+                artists: artists.join(", "),
+                artist_names: artists.iter().map(|a| a.to_string()).collect(),
+                ..problem(id, 0)
+            });
+        }
+        // A report from before `artist_names` existed.
+        summary.add_problem(ProblematicTrack {
+            artists: "ABBA".to_string(),
+            artist_names: Vec::new(),
+            ..problem("4", 0)
+        });
+
+        let groups = summary.group_by_artist();
+        let ids =
+            |artist: &str| -> Vec<&str> { groups[artist].iter().map(|t| t.id.as_str()).collect() };
+        assert_eq!(groups.len(), 4);
+        assert_eq!(ids("Queen"), vec!["1", "2"]);
+        assert_eq!(ids("David Bowie"), vec!["2"]);
+        assert_eq!(ids("Tyler, The Creator"), vec!["3"]);
+        assert_eq!(ids("ABBA"), vec!["4"]);
     }
 }
@@ -0,0 +1,1187 @@
+/*
+    spotify-audit-rs | Rust CLI tool to audit playlists and sync Liked Songs.
+    Copyright (C) 2025  Israel Alberto Roldan Vega
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::models::{
+    AlbumCompleteness, ArtistRef, AuditStats, AuditSummary, IsrcCluster, LibraryStats, MergeReport,
+    PlaylistDiff, PlaylistSummary, PlaylistTrackCount, ProblematicTrack, SyncDirection, SyncReport,
+    TrackInspection, TrackSummary,
+};
+use crate::util::format_duration;
+use serde::Serialize;
+use std::fmt::Write;
+use std::str::FromStr;
+use thiserror::Error;
+
+const SEPARATOR: &str = "---------------------------------------------------";
+
+#[derive(Error, Debug)]
+pub enum OutputError {
+    #[error("JSON serialization failed: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("CSV serialization failed: {0}")]
+    Csv(#[from] csv::Error),
+}
+
+/// Report formats selectable with the global `--format` flag.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable output (each command's own layout)
+    #[default]
+    Table,
+    /// Pretty-printed JSON of the full report
+    Json,
+    /// Comma-separated flat table
+    Csv,
+    /// Tab-separated flat table
+    Tsv,
+    /// Markdown document or table
+    Markdown,
+}
+
+impl OutputFormat {
+    /// Names accepted by `FromStr`, for argument parsers and completions.
+    pub const NAMES: [&'static str; 5] = ["table", "json", "csv", "tsv", "markdown"];
+
+    /// The formatter that renders a `Report` in this format.
+    pub fn formatter(self) -> Box<dyn Formatter> {
+        match self {
+            OutputFormat::Table => Box::new(TableFormatter),
+            OutputFormat::Json => Box::new(JsonFormatter),
+            OutputFormat::Csv => Box::new(DelimitedFormatter { delimiter: b',' }),
+            OutputFormat::Tsv => Box::new(DelimitedFormatter { delimiter: b'\t' }),
+            OutputFormat::Markdown => Box::new(MarkdownFormatter),
+        }
+    }
+
+    /// Shorthand for `self.formatter().format(report)`.
+    pub fn render(self, report: &dyn Report) -> Result<String, OutputError> {
+        self.formatter().format(report)
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            // "text" was the name of the human format before it became global.
+            "table" | "text" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "tsv" => Ok(OutputFormat::Tsv),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            other => Err(format!(
+                "unknown format '{}' (expected one of: {})",
+                other,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// A report flattened into a header and rows, for the table-shaped formats.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    pub headers: Vec<&'static str>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Something a `Formatter` can render: as JSON, or flattened into a `Table`.
+pub trait Report {
+    fn to_json(&self) -> Result<serde_json::Value, OutputError>;
+    fn to_table(&self) -> Table;
+}
+
+/// Renders a `Report` in one output format.
+pub trait Formatter {
+    fn format(&self, report: &dyn Report) -> Result<String, OutputError>;
+}
+
+/// Aligned columns separated by `|`, the layout of the human-readable listings.
+pub struct TableFormatter;
+
+impl Formatter for TableFormatter {
+    fn format(&self, report: &dyn Report) -> Result<String, OutputError> {
+        let table = report.to_table();
+        let mut widths: Vec<usize> = table.headers.iter().map(|h| h.chars().count()).collect();
+        for row in &table.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let line = |cells: Vec<String>| -> String {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join(" | ")
+                .trim_end()
+                .to_string()
+        };
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{}",
+            line(table.headers.iter().map(|h| h.to_string()).collect())
+        );
+        let _ = writeln!(
+            out,
+            "{}",
+            widths
+                .iter()
+                .map(|width| "-".repeat(*width))
+                .collect::<Vec<_>>()
+                .join("-+-")
+        );
+        for row in table.rows {
+            let _ = writeln!(out, "{}", line(row));
+        }
+        Ok(out)
+    }
+}
+
+/// Pretty-printed JSON of the whole report.
+pub struct JsonFormatter;
+
+impl Formatter for JsonFormatter {
+    fn format(&self, report: &dyn Report) -> Result<String, OutputError> {
+        let mut out = serde_json::to_string_pretty(&report.to_json()?)?;
+        out.push('\n');
+        Ok(out)
+    }
+}
+
+/// The header row once, then one record per row. Fields containing the delimiter,
+/// quotes or newlines are quoted by the `csv` writer.
+pub struct DelimitedFormatter {
+    pub delimiter: u8,
+}
+
+impl Formatter for DelimitedFormatter {
+    fn format(&self, report: &dyn Report) -> Result<String, OutputError> {
+        let table = report.to_table();
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .from_writer(Vec::new());
+        writer.write_record(&table.headers)?;
+        for row in &table.rows {
+            writer.write_record(row)?;
+        }
+        let bytes = writer
+            .into_inner()
+            .map_err(|e| csv::Error::from(e.into_error()))?;
+        // Every field came from a `String`, so the output is valid UTF-8.
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+/// A Markdown table with escaped cells.
+pub struct MarkdownFormatter;
+
+impl Formatter for MarkdownFormatter {
+    fn format(&self, report: &dyn Report) -> Result<String, OutputError> {
+        let table = report.to_table();
+        let mut out = String::new();
+        let _ = writeln!(out, "| {} |", table.headers.join(" | "));
+        let _ = writeln!(out, "|{}", "---|".repeat(table.headers.len()));
+        for row in &table.rows {
+            let cells: Vec<String> = row.iter().map(|cell| markdown_cell(cell)).collect();
+            let _ = writeln!(out, "| {} |", cells.join(" | "));
+        }
+        Ok(out)
+    }
+}
+
+/// Pairs any serializable value with a table view of it, for reports that have no
+/// `Report` impl of their own (e.g. a plain map of counts).
+pub struct Tabulated<'a, T: Serialize + ?Sized> {
+    value: &'a T,
+    table: Table,
+}
+
+impl<'a, T: Serialize + ?Sized> Tabulated<'a, T> {
+    pub fn new(value: &'a T, table: Table) -> Self {
+        Self { value, table }
+    }
+}
+
+impl<T: Serialize + ?Sized> Report for Tabulated<'_, T> {
+    fn to_json(&self) -> Result<serde_json::Value, OutputError> {
+        Ok(serde_json::to_value(self.value)?)
+    }
+
+    fn to_table(&self) -> Table {
+        self.table.clone()
+    }
+}
+
+impl Report for AuditSummary {
+    fn to_json(&self) -> Result<serde_json::Value, OutputError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    fn to_table(&self) -> Table {
+        Table {
+            headers: PROBLEMATIC_TRACK_HEADER.to_vec(),
+            rows: problematic_track_rows(&self.problematic_tracks),
+        }
+    }
+}
+
+impl Report for Vec<ProblematicTrack> {
+    fn to_json(&self) -> Result<serde_json::Value, OutputError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    fn to_table(&self) -> Table {
+        Table {
+            headers: PROBLEMATIC_TRACK_HEADER.to_vec(),
+            rows: problematic_track_rows(self),
+        }
+    }
+}
+
+impl Report for SyncReport {
+    fn to_json(&self) -> Result<serde_json::Value, OutputError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    /// One row per track of every batch.
+    fn to_table(&self) -> Table {
+        let rows = self
+            .batch_logs
+            .iter()
+            .flat_map(|batch| {
+                batch.track_ids.iter().map(move |id| {
+                    vec![
+                        batch.batch_index.to_string(),
+                        id.clone(),
+                        batch.status.clone(),
+                    ]
+                })
+            })
+            .collect();
+        Table {
+            headers: vec!["batch_index", "track_id", "status"],
+            rows,
+        }
+    }
+}
+
+impl Report for Vec<AlbumCompleteness> {
+    fn to_json(&self) -> Result<serde_json::Value, OutputError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    fn to_table(&self) -> Table {
+        Table {
+            headers: vec![
+                "album_id",
+                "name",
+                "artists",
+                "saved_tracks",
+                "total_tracks",
+                "missing_tracks",
+            ],
+            rows: self
+                .iter()
+                .map(|album| {
+                    vec![
+                        album.album_id.clone(),
+                        album.name.clone(),
+                        album.artists.clone(),
+                        album.saved_tracks.to_string(),
+                        album.total_tracks.to_string(),
+                        album.missing_label(),
+                    ]
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Report for MergeReport {
+    fn to_json(&self) -> Result<serde_json::Value, OutputError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    /// One row per source playlist.
+    fn to_table(&self) -> Table {
+        Table {
+            headers: vec!["source_id", "added", "skipped"],
+            rows: self
+                .sources
+                .iter()
+                .map(|source| {
+                    vec![
+                        source.playlist_id.clone(),
+                        source.added.to_string(),
+                        source.skipped.to_string(),
+                    ]
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Report for Vec<ArtistRef> {
+    fn to_json(&self) -> Result<serde_json::Value, OutputError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    fn to_table(&self) -> Table {
+        Table {
+            headers: vec!["artist_id", "artist", "tracks"],
+            rows: self
+                .iter()
+                .map(|artist| {
+                    vec![
+                        artist.id.clone(),
+                        artist.name.clone(),
+                        artist.saved_tracks.to_string(),
+                    ]
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Report for PlaylistTrackCount {
+    fn to_json(&self) -> Result<serde_json::Value, OutputError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    fn to_table(&self) -> Table {
+        Table {
+            headers: vec!["playlist_id", "tracks"],
+            rows: vec![vec![self.playlist_id.clone(), self.tracks.to_string()]],
+        }
+    }
+}
+
+impl Report for Vec<PlaylistSummary> {
+    fn to_json(&self) -> Result<serde_json::Value, OutputError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    fn to_table(&self) -> Table {
+        let rows = self
+            .iter()
+            .map(|pl| {
+                vec![
+                    pl.id.clone(),
+                    pl.name.clone(),
+                    pl.owner_name.clone(),
+                    pl.total_tracks.to_string(),
+                    pl.is_public.to_string(),
+                    pl.is_collaborative.to_string(),
+                ]
+            })
+            .collect();
+        Table {
+            headers: vec!["id", "name", "owner", "tracks", "public", "collaborative"],
+            rows,
+        }
+    }
+}
+
+impl Report for Vec<TrackInspection> {
+    fn to_json(&self) -> Result<serde_json::Value, OutputError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    fn to_table(&self) -> Table {
+        track_inspection_table(self)
+    }
+}
+
+impl Report for TrackInspection {
+    fn to_json(&self) -> Result<serde_json::Value, OutputError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    fn to_table(&self) -> Table {
+        track_inspection_table(std::slice::from_ref(self))
+    }
+}
+
+impl Report for Vec<TrackSummary> {
+    fn to_json(&self) -> Result<serde_json::Value, OutputError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    fn to_table(&self) -> Table {
+        Table {
+            headers: TRACK_SUMMARY_HEADER.to_vec(),
+            rows: self.iter().map(track_summary_row).collect(),
+        }
+    }
+}
+
+impl Report for PlaylistDiff {
+    fn to_json(&self) -> Result<serde_json::Value, OutputError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    fn to_table(&self) -> Table {
+        track_sections_table(&[
+            ("only_in_a", &self.only_in_a),
+            ("only_in_b", &self.only_in_b),
+            ("in_both", &self.in_both),
+        ])
+    }
+}
+
+/// One row per track, the first column naming the section it was listed under.
+pub fn track_sections_table(sections: &[(&str, &[TrackSummary])]) -> Table {
+    let mut headers = vec!["section"];
+    headers.extend(TRACK_SUMMARY_HEADER);
+    let rows = sections
+        .iter()
+        .flat_map(|(section, tracks)| {
+            tracks.iter().map(move |track| {
+                let mut row = vec![section.to_string()];
+                row.extend(track_summary_row(track));
+                row
+            })
+        })
+        .collect();
+    Table { headers, rows }
+}
+
+impl Report for LibraryStats {
+    fn to_json(&self) -> Result<serde_json::Value, OutputError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    /// One row per metric, then one per decade.
+    fn to_table(&self) -> Table {
+        let mut rows: Vec<Vec<String>> = [
+            ("total_tracks", self.total_tracks),
+            ("unplayable", self.unplayable),
+            ("zero_markets", self.zero_markets),
+            ("unique_artists", self.unique_artists),
+            ("unique_albums", self.unique_albums),
+            ("local_files", self.local_files),
+        ]
+        .into_iter()
+        .map(|(metric, value)| vec![metric.to_string(), value.to_string()])
+        .collect();
+        rows.push(vec![
+            "total_duration_ms".to_string(),
+            self.total_duration_ms.to_string(),
+        ]);
+        rows.extend(
+            self.decades
+                .iter()
+                .map(|(decade, count)| vec![format!("decade_{}", decade), count.to_string()]),
+        );
+        Table {
+            headers: vec!["metric", "value"],
+            rows,
+        }
+    }
+}
+
+impl Report for Vec<IsrcCluster> {
+    fn to_json(&self) -> Result<serde_json::Value, OutputError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    /// One row per occurrence.
+    fn to_table(&self) -> Table {
+        let rows = self
+            .iter()
+            .flat_map(|cluster| {
+                cluster.occurrences.iter().map(move |occurrence| {
+                    vec![
+                        cluster.isrc.clone(),
+                        occurrence.playlist_id.clone(),
+                        occurrence.playlist_name.clone(),
+                        occurrence.track_id.clone(),
+                        occurrence.track_name.clone(),
+                    ]
+                })
+            })
+            .collect();
+        Table {
+            headers: vec![
+                "isrc",
+                "playlist_id",
+                "playlist_name",
+                "track_id",
+                "track_name",
+            ],
+            rows,
+        }
+    }
+}
+
+/// Renders the human-readable scan report grouped by artist, the artists with the
+/// most problematic tracks first (ties by name). Without `colored`, statuses use ASCII
+/// tags instead of emoji.
+pub fn render_scan_by_artist(summary: &AuditSummary, target: &str, colored: bool) -> String {
+    let mut groups: Vec<_> = summary.group_by_artist().into_iter().collect();
+    groups.sort_by(|(a, a_tracks), (b, b_tracks)| {
+        b_tracks.len().cmp(&a_tracks.len()).then_with(|| a.cmp(b))
+    });
+
+    let mut out = String::new();
+    let _ = writeln!(out, "{}", SEPARATOR);
+    let _ = writeln!(out, "AUDIT REPORT BY ARTIST");
+    let _ = writeln!(out, "{}", SEPARATOR);
+    let _ = writeln!(out, "Target:               {}", target);
+    let _ = writeln!(
+        out,
+        "Total Tracks Scanned: {}",
+        summary.total_tracks_scanned
+    );
+    let _ = writeln!(
+        out,
+        "Problematic Tracks:   {}",
+        summary.problematic_tracks.len()
+    );
+    let _ = writeln!(out, "Artists Affected:     {}", groups.len());
+    if let Some(start) = &summary.scan_window_start {
+        let _ = writeln!(out, "Saved Since:          {}", start);
+    }
+    if summary.truncated {
+        let _ = writeln!(out, "Truncated:            stopped at the scan limit");
+    }
+    let _ = writeln!(out, "{}", SEPARATOR);
+
+    for (artist, tracks) in &groups {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "{} problematic tracks by {}:", tracks.len(), artist);
+        for track in tracks {
+            let _ = writeln!(
+                out,
+                "   - {} ({}) | {}",
+                track.name,
+                track.album,
+                track.status_with_color(colored)
+            );
+        }
+    }
+    out
+}
+
+/// Renders a scan report. `target` names what was scanned (e.g. "Liked Songs").
+/// With `with_stats`, the `AuditStats` breakdown is appended (as a `stats` key in JSON;
+/// CSV and TSV stay one record per track). Without `colored`, the table report uses
+/// ASCII status tags instead of emoji; the other formats do not depend on it.
+pub fn render_scan(
+    summary: &AuditSummary,
+    target: &str,
+    format: OutputFormat,
+    with_stats: bool,
+    colored: bool,
+) -> Result<String, OutputError> {
+    let stats = with_stats.then(|| summary.stats());
+    match format {
+        OutputFormat::Table => {
+            let mut out = scan_text(summary, target, colored);
+            if let Some(stats) = &stats {
+                out.push_str(&stats_text(stats));
+            }
+            Ok(out)
+        }
+        OutputFormat::Json => {
+            let mut value = summary.to_json()?;
+            if let (Some(stats), Some(object)) = (&stats, value.as_object_mut()) {
+                object.insert("stats".to_string(), serde_json::to_value(stats)?);
+            }
+            let mut out = serde_json::to_string_pretty(&value)?;
+            out.push('\n');
+            Ok(out)
+        }
+        OutputFormat::Csv | OutputFormat::Tsv => format.render(summary),
+        OutputFormat::Markdown => {
+            let mut out = scan_markdown(summary, target);
+            if let Some(stats) = &stats {
+                out.push_str(&stats_markdown(stats));
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// Renders a sync report, in either direction.
+pub fn render_sync(report: &SyncReport, format: OutputFormat) -> Result<String, OutputError> {
+    match format {
+        OutputFormat::Table => Ok(sync_text(report)),
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Tsv => format.render(report),
+        OutputFormat::Markdown => Ok(sync_markdown(report)),
+    }
+}
+
+fn scan_text(summary: &AuditSummary, target: &str, colored: bool) -> String {
+    let mut out = String::new();
+    // Writing into a String cannot fail, hence the ignored results.
+    let _ = writeln!(out, "{}", SEPARATOR);
+    let _ = writeln!(out, "AUDIT REPORT");
+    let _ = writeln!(out, "{}", SEPARATOR);
+    let _ = writeln!(out, "Target:               {}", target);
+    let _ = writeln!(
+        out,
+        "Total Tracks Scanned: {}",
+        summary.total_tracks_scanned
+    );
+    let _ = writeln!(
+        out,
+        "Total Duration:       {}",
+        format_duration(summary.total_duration_ms)
+    );
+    let _ = writeln!(out, "Explicit Tracks:      {}", summary.explicit_count);
+    let _ = writeln!(
+        out,
+        "Problematic Tracks:   {}",
+        summary.problematic_tracks.len()
+    );
+    let _ = writeln!(
+        out,
+        "Problematic Duration: {}",
+        format_duration(summary.problematic_duration_ms())
+    );
+    if let Some(start) = &summary.scan_window_start {
+        let _ = writeln!(out, "Saved Since:          {}", start);
+    }
+    if summary.truncated {
+        let _ = writeln!(out, "Truncated:            stopped at the scan limit");
+    }
+    let _ = writeln!(out, "{}", SEPARATOR);
+
+    if !summary.problematic_tracks.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Found the following issues:");
+        for (i, track) in summary.problematic_tracks.iter().enumerate() {
+            let _ = writeln!(out, "{}. {}", i + 1, track.format_with_color(colored));
+        }
+
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Legend:");
+        let _ = writeln!(
+            out,
+            "  [REMOVED GLOBALLY]: Track has been removed from Spotify entirely (0 markets)."
+        );
+        let _ = writeln!(
+            out,
+            "  [GEO-LOCKED]:       Track is available in other countries but restricted in yours."
+        );
+        let _ = writeln!(
+            out,
+            "  [UNPLAYABLE]:       Spotify did not report where else the track is available."
+        );
+    } else if summary.truncated {
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "No unplayable tracks in the {} tracks scanned; the rest was not checked.",
+            summary.total_tracks_scanned
+        );
+    } else {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "No unplayable tracks found. Clean!");
+    }
+    out
+}
+
+fn stats_text(stats: &AuditStats) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out);
+    let _ = writeln!(out, "{}", SEPARATOR);
+    let _ = writeln!(out, "STATISTICS");
+    let _ = writeln!(out, "{}", SEPARATOR);
+    let _ = writeln!(out, "Removed Globally:     {}", stats.globally_removed);
+    let _ = writeln!(out, "Geo-Locked:           {}", stats.geo_locked);
+    let _ = writeln!(out, "Duplicates:           {}", stats.duplicates);
+    let _ = writeln!(out, "Explicit:             {}", stats.explicit);
+    let _ = writeln!(out, "Other:                {}", stats.other);
+    let _ = writeln!(
+        out,
+        "Problematic:          {} ({:.1}% of scanned)",
+        stats.total_problematic, stats.percent_problematic
+    );
+    if !stats.markets_histogram.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Still available in (market: tracks):");
+        for (market, count) in &stats.markets_histogram {
+            let _ = writeln!(out, "  {}: {}", market, count);
+        }
+    }
+    let _ = writeln!(out, "{}", SEPARATOR);
+    out
+}
+
+fn stats_markdown(stats: &AuditStats) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out);
+    let _ = writeln!(out, "## Statistics");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| Metric | Value |");
+    let _ = writeln!(out, "|---|---|");
+    let _ = writeln!(out, "| Removed Globally | {} |", stats.globally_removed);
+    let _ = writeln!(out, "| Geo-Locked | {} |", stats.geo_locked);
+    let _ = writeln!(out, "| Duplicates | {} |", stats.duplicates);
+    let _ = writeln!(out, "| Explicit | {} |", stats.explicit);
+    let _ = writeln!(out, "| Other | {} |", stats.other);
+    let _ = writeln!(
+        out,
+        "| Problematic | {} ({:.1}%) |",
+        stats.total_problematic, stats.percent_problematic
+    );
+    if !stats.markets_histogram.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Market | Still available |");
+        let _ = writeln!(out, "|---|---|");
+        for (market, count) in &stats.markets_histogram {
+            let _ = writeln!(out, "| {} | {} |", market, count);
+        }
+    }
+    out
+}
+
+fn scan_markdown(summary: &AuditSummary, target: &str) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Audit Report: {}", markdown_cell(target));
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "Scanned **{}** tracks ({}), found **{}** problematic ({}).",
+        summary.total_tracks_scanned,
+        format_duration(summary.total_duration_ms),
+        summary.problematic_tracks.len(),
+        format_duration(summary.problematic_duration_ms())
+    );
+    if let Some(start) = &summary.scan_window_start {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "_Only tracks saved since {} were scanned._", start);
+    }
+    if summary.truncated {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "_Truncated: the scan stopped at its track limit._");
+    }
+
+    if !summary.problematic_tracks.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Name | Artists | Album | Status | Link |");
+        let _ = writeln!(out, "|---|---|---|---|---|");
+        for track in &summary.problematic_tracks {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | [Open]({}) |",
+                markdown_cell(&track.name),
+                markdown_cell(&track.artists),
+                markdown_cell(&track.album),
+                track.status(),
+                track.external_url
+            );
+        }
+    }
+    out
+}
+
+/// Names of the sync target, the sync source and the skipped tracks, per direction.
+fn sync_labels(direction: SyncDirection) -> (&'static str, &'static str, &'static str) {
+    match direction {
+        SyncDirection::PlaylistToLiked => ("Liked Songs", "Source Playlist", "Already Liked"),
+        SyncDirection::LikedToPlaylist => ("Playlist Tracks", "Liked Songs", "In Playlist"),
+    }
+}
+
+fn sync_text(report: &SyncReport) -> String {
+    let (target, source, skipped) = sync_labels(report.direction);
+    let mut out = String::new();
+    let _ = writeln!(out, "{}", SEPARATOR);
+    if report.dry_run {
+        let _ = writeln!(out, "SYNC PREVIEW (DRY RUN - library not modified)");
+    } else {
+        let _ = writeln!(out, "SYNC COMPLETE");
+    }
+    let _ = writeln!(out, "{}", SEPARATOR);
+    let _ = writeln!(
+        out,
+        "{:<26}{}",
+        format!("Initial {}:", target),
+        report.initial_liked_count
+    );
+    let _ = writeln!(
+        out,
+        "{:<26}{}",
+        format!("Tracks in {}:", source),
+        report.total_tracks_in_playlist
+    );
+    let _ = writeln!(out, "Tracks Processed:         {}", report.tracks_processed);
+    let _ = writeln!(
+        out,
+        "{:<26}{}",
+        format!("Skipped ({}):", skipped),
+        report.skipped_already_liked
+    );
+    let _ = writeln!(
+        out,
+        "{:<26}{}",
+        format!("Final {}:", target),
+        report.final_liked_count
+    );
+    let _ = writeln!(out, "{}", SEPARATOR);
+    if report.dry_run {
+        let _ = writeln!(
+            out,
+            "Tracks That Would Be Added: {}",
+            report.estimated_added
+        );
+    } else {
+        let _ = writeln!(
+            out,
+            "Estimated New Tracks Added: {}",
+            report.estimated_added
+        );
+        let _ = writeln!(
+            out,
+            "Confirmed Added (delta):    {}",
+            report.confirmed_added
+        );
+    }
+    let _ = writeln!(out, "{}", SEPARATOR);
+    out
+}
+
+fn sync_markdown(report: &SyncReport) -> String {
+    let (target, source, skipped) = sync_labels(report.direction);
+    let mut out = String::new();
+    if report.dry_run {
+        let _ = writeln!(out, "# Sync Preview (dry run)");
+    } else {
+        let _ = writeln!(out, "# Sync Report");
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| Metric | Count |");
+    let _ = writeln!(out, "|---|---|");
+    let _ = writeln!(
+        out,
+        "| Initial {} | {} |",
+        target, report.initial_liked_count
+    );
+    let _ = writeln!(
+        out,
+        "| Tracks in {} | {} |",
+        source, report.total_tracks_in_playlist
+    );
+    let _ = writeln!(out, "| Tracks Processed | {} |", report.tracks_processed);
+    let _ = writeln!(
+        out,
+        "| Skipped ({}) | {} |",
+        skipped, report.skipped_already_liked
+    );
+    let _ = writeln!(out, "| Final {} | {} |", target, report.final_liked_count);
+    let _ = writeln!(out, "| Estimated Added | {} |", report.estimated_added);
+    if !report.dry_run {
+        let _ = writeln!(out, "| Confirmed Added | {} |", report.confirmed_added);
+    }
+
+    if !report.batch_logs.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Batch | Tracks | Status |");
+        let _ = writeln!(out, "|---|---|---|");
+        for batch in &report.batch_logs {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} |",
+                batch.batch_index,
+                batch.tracks_count,
+                markdown_cell(&batch.status)
+            );
+        }
+    }
+    out
+}
+
+/// Escapes characters that would break a Markdown table cell.
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+const PROBLEMATIC_TRACK_HEADER: [&str; 7] = [
+    "id",
+    "name",
+    "artists",
+    "album",
+    "reason",
+    "available_markets_count",
+    "external_url",
+];
+
+fn problematic_track_rows(tracks: &[ProblematicTrack]) -> Vec<Vec<String>> {
+    tracks
+        .iter()
+        .map(|track| {
+            vec![
+                track.id.clone(),
+                track.name.clone(),
+                track.artists.clone(),
+                track.album.clone(),
+                track.reason.clone(),
+                track.available_markets_count.to_string(),
+                track.external_url.clone(),
+            ]
+        })
+        .collect()
+}
+
+fn track_inspection_table(tracks: &[TrackInspection]) -> Table {
+    let rows = tracks
+        .iter()
+        .map(|track| {
+            vec![
+                track.id.clone(),
+                track.name.clone(),
+                track.artists.join(", "),
+                track.album.clone(),
+                track.release_date.clone(),
+                track.duration_ms.to_string(),
+                track.popularity.to_string(),
+                track
+                    .is_playable
+                    .map_or(String::new(), |playable| playable.to_string()),
+                track.available_markets.len().to_string(),
+                track.external_ids.get("isrc").cloned().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    Table {
+        headers: vec![
+            "id",
+            "name",
+            "artists",
+            "album",
+            "release_date",
+            "duration_ms",
+            "popularity",
+            "is_playable",
+            "available_markets_count",
+            "isrc",
+        ],
+        rows,
+    }
+}
+
+const TRACK_SUMMARY_HEADER: [&str; 4] = ["id", "name", "artists", "isrc"];
+
+fn track_summary_row(track: &TrackSummary) -> Vec<String> {
+    vec![
+        track.id.clone(),
+        track.name.clone(),
+        track.artists.join(", "),
+        track.isrc.clone().unwrap_or_default(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::problem;
+    use crate::models::MergeSource;
+
+    #[test]
+    fn test_scan_markdown_table() {
+        let mut summary = AuditSummary::new();
+        summary.total_tracks_scanned = 10;
+        summary.total_duration_ms = 3_725_000;
+        summary.add_problem(ProblematicTrack {
+            name: "Gone | Live".to_string(),
+            external_url: "https://open.spotify.com/track/1".to_string(),
+            ..problem("spotify:track:1", 0)
+        });
+        summary.add_problem(ProblematicTrack {
+            name: "Elsewhere".to_string(),
+            duration_ms: 185_000,
+            ..problem("spotify:track:1", 3)
+        });
+
+        let markdown =
+            render_scan(&summary, "Liked Songs", OutputFormat::Markdown, false, true).unwrap();
+        let lines: Vec<&str> = markdown.lines().collect();
+
+        assert_eq!(lines[0], "# Audit Report: Liked Songs");
+        assert_eq!(
+            lines[2],
+            "Scanned **10** tracks (01:02:05), found **2** problematic (00:03:05)."
+        );
+        assert_eq!(lines[4], "| Name | Artists | Album | Status | Link |");
+        assert_eq!(
+            lines[6],
+            "| Gone \\| Live | Artist | Album | 🔴 REMOVED GLOBALLY | [Open](https://open.spotify.com/track/1) |"
+        );
+        assert!(lines[7].contains("🌍 GEO-LOCKED (Available in 3 markets)"));
+    }
+
+    #[test]
+    fn test_scan_json_stats_key() {
+        let mut summary = AuditSummary::new();
+        summary.total_tracks_scanned = 4;
+        summary.add_problem(ProblematicTrack {
+            name: "Gone".to_string(),
+            ..problem("spotify:track:1", 0)
+        });
+
+        let plain = render_scan(&summary, "Liked Songs", OutputFormat::Json, false, true).unwrap();
+        assert!(!plain.contains("\"stats\""));
+        assert!(plain.ends_with("}\n"));
+
+        let json = render_scan(&summary, "Liked Songs", OutputFormat::Json, true, true).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["stats"]["globally_removed"], 1);
+        assert_eq!(value["stats"]["percent_problematic"], 25.0);
+        assert_eq!(value["total_tracks_scanned"], 4);
+    }
+
+    #[test]
+    fn test_scan_markdown_clean() {
+        let summary = AuditSummary::new();
+        let markdown =
+            render_scan(&summary, "Playlist", OutputFormat::Markdown, false, true).unwrap();
+        assert!(markdown.contains("found **0** problematic"));
+        assert!(!markdown.contains("| Name |"));
+    }
+
+    #[test]
+    fn test_sync_text_labels_follow_direction() {
+        let forward = sync_text(&SyncReport::default());
+        assert!(forward.contains("Initial Liked Songs:      0"));
+        assert!(forward.contains("Tracks in Source Playlist:0"));
+
+        let reverse = sync_text(&SyncReport {
+            direction: SyncDirection::LikedToPlaylist,
+            ..Default::default()
+        });
+        assert!(reverse.contains("Initial Playlist Tracks:  0"));
+        assert!(reverse.contains("Skipped (In Playlist):    0"));
+        assert!(!reverse.contains("Source Playlist"));
+    }
+
+    #[test]
+    fn test_scan_text_truncated_is_not_clean() {
+        let summary = AuditSummary {
+            total_tracks_scanned: 10,
+            truncated: true,
+            ..Default::default()
+        };
+        let text = render_scan(&summary, "Liked Songs", OutputFormat::Table, false, true).unwrap();
+        assert!(text.contains("Truncated:"));
+        assert!(!text.contains("Clean!"));
+
+        let json = render_scan(&summary, "Liked Songs", OutputFormat::Json, false, true).unwrap();
+        assert!(json.contains("\"truncated\": true"));
+    }
+
+    #[test]
+    fn test_scan_text_shows_window_start() {
+        let summary = AuditSummary {
+            scan_window_start: Some("2025-01-01".to_string()),
+            ..Default::default()
+        };
+        let text = render_scan(&summary, "Liked Songs", OutputFormat::Table, false, true).unwrap();
+        assert!(text.contains("Saved Since:          2025-01-01"));
+
+        let full = render_scan(
+            &AuditSummary::new(),
+            "Liked Songs",
+            OutputFormat::Json,
+            false,
+            true,
+        );
+        assert!(!full.unwrap().contains("scan_window_start"));
+    }
+
+    #[test]
+    fn test_delimited_formatters() {
+        let mut summary = AuditSummary::new();
+        summary.add_problem(ProblematicTrack {
+            name: "Gone, Live".to_string(),
+            ..problem("spotify:track:1", 0)
+        });
+
+        let csv = OutputFormat::Csv.render(&summary).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "id,name,artists,album,reason,available_markets_count,external_url"
+        );
+        assert!(lines[1].contains("\"Gone, Live\""));
+
+        let tsv = OutputFormat::Tsv.render(&summary).unwrap();
+        let fields: Vec<&str> = tsv.lines().nth(1).unwrap().split('\t').collect();
+        assert_eq!(fields[1], "Gone, Live");
+        assert_eq!(fields[5], "0");
+    }
+
+    #[test]
+    fn test_table_formatter_aligns_columns() {
+        let counts = vec![("Queen", 12), ("ABBA", 3)];
+        let table = Table {
+            headers: vec!["artist", "tracks"],
+            rows: counts
+                .iter()
+                .map(|(artist, count)| vec![artist.to_string(), count.to_string()])
+                .collect(),
+        };
+        let out = OutputFormat::Table
+            .render(&Tabulated::new(&counts, table))
+            .unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "artist | tracks");
+        assert_eq!(lines[1], "-------+-------");
+        assert_eq!(lines[2], "Queen  | 12");
+        assert_eq!(lines[3], "ABBA   | 3");
+    }
+
+    #[test]
+    fn test_merge_report_lists_sources_in_order() {
+        let report = MergeReport {
+            destination_id: "dest".to_string(),
+            dedup: true,
+            sources: vec![
+                MergeSource {
+                    playlist_id: "b".to_string(),
+                    added: 3,
+                    skipped: 0,
+                },
+                MergeSource {
+                    playlist_id: "a".to_string(),
+                    added: 1,
+                    skipped: 2,
+                },
+            ],
+            total_added: 4,
+            total_skipped: 2,
+        };
+        let tsv = OutputFormat::Tsv.render(&report).unwrap();
+        assert_eq!(tsv, "source_id\tadded\tskipped\nb\t3\t0\na\t1\t2\n");
+    }
+
+    #[test]
+    fn test_output_format_from_str() {
+        assert_eq!("TSV".parse(), Ok(OutputFormat::Tsv));
+        assert_eq!("text".parse(), Ok(OutputFormat::Table));
+        assert!("yaml".parse::<OutputFormat>().is_err());
+    }
+}
@@ -0,0 +1,307 @@
+/*
+    spotify-audit-rs | Rust CLI tool to audit playlists and sync Liked Songs.
+    Copyright (C) 2025  Israel Alberto Roldan Vega
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use chrono::NaiveDate;
+use rspotify::model::{Country, Market, PlaylistId, TrackId};
+
+/// Spotify object types whose IDs we accept as input.
+const ID_KINDS: [&str; 2] = ["playlist", "track"];
+
+/// Extracts a bare Spotify ID from user input.
+///
+/// Accepts:
+/// - Bare IDs (`37i9dQZF1DXcBWIGoYBM5M`)
+/// - URIs (`spotify:playlist:<id>`, `spotify:track:<id>`)
+/// - Share URLs (`https://open.spotify.com/playlist/<id>?si=...`), including
+///   localized paths such as `/intl-es/track/<id>`.
+///
+/// Returns `None` when no alphanumeric ID can be extracted.
+pub fn normalize_id(input: &str) -> Option<String> {
+    extract_id(input, &ID_KINDS).map(str::to_string)
+}
+
+/// Parses a playlist from a bare ID, a `spotify:playlist:` URI or a playlist URL.
+/// URIs and URLs pointing at another kind of object (e.g. a track) are rejected.
+pub fn parse_playlist_id(input: &str) -> Option<PlaylistId<'static>> {
+    let id = extract_id(input, &["playlist"])?;
+    PlaylistId::from_id(id.to_string()).ok()
+}
+
+/// Parses a playlist from a share URL such as
+/// `https://open.spotify.com/playlist/<id>?si=...`. Unlike `parse_playlist_id`, bare
+/// IDs and URIs are rejected, as are URLs of other objects.
+pub fn parse_playlist_url(url: &str) -> Option<PlaylistId<'static>> {
+    let url = url.trim();
+    if url.starts_with("https://") || url.starts_with("http://") {
+        parse_playlist_id(url)
+    } else {
+        None
+    }
+}
+
+/// Parses a track from a bare ID, a `spotify:track:` URI or a track URL.
+/// URIs and URLs pointing at another kind of object (e.g. a playlist) are rejected.
+pub fn parse_track_id(input: &str) -> Option<TrackId<'static>> {
+    let id = extract_id(input, &["track"])?;
+    TrackId::from_id(id.to_string()).ok()
+}
+
+/// Shared parser behind the ID helpers; `kinds` lists the object types accepted
+/// in URIs and URLs. Bare IDs carry no kind and are always accepted.
+fn extract_id<'a>(input: &'a str, kinds: &[&str]) -> Option<&'a str> {
+    let input = input.trim();
+
+    let id = if let Some(rest) = input.strip_prefix("spotify:") {
+        let (kind, id) = rest.split_once(':')?;
+        if !kinds.contains(&kind) {
+            return None;
+        }
+        id
+    } else if input.starts_with("https://") || input.starts_with("http://") {
+        let without_query = input.split(['?', '#']).next()?;
+        let (_, path) = without_query.split_once("open.spotify.com/")?;
+        let mut segments = path.split('/').filter(|s| !s.is_empty());
+        // Skip locale prefixes such as `intl-es` up to the first known object type.
+        let kind = segments.find(|s| ID_KINDS.contains(s))?;
+        if !kinds.contains(&kind) {
+            return None;
+        }
+        segments.next()?
+    } else {
+        input
+    };
+
+    if !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        Some(id)
+    } else {
+        None
+    }
+}
+
+/// Parses a two-letter ISO 3166-1 country code (case-insensitive) into a `Market`.
+pub fn parse_market(code: &str) -> Option<Market> {
+    let code = code.trim().to_ascii_uppercase();
+    if code.len() != 2 {
+        return None;
+    }
+    // `Country` only implements (de)serialization with the ISO codes as names.
+    serde_json::from_value::<Country>(serde_json::Value::String(code))
+        .ok()
+        .map(Market::Country)
+}
+
+/// Parses a `YYYY-MM-DD` calendar date.
+pub fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok()
+}
+
+/// Formats a duration in milliseconds as `HH:MM:SS`; hours grow past two digits.
+pub fn format_duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Similarity of two strings between 0.0 (nothing in common) and 1.0 (identical),
+/// computed as `1 - levenshtein / longest length` over characters.
+pub fn name_similarity(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    // Single-row Levenshtein: `row[j]` is the distance between a[..i] and b[..j].
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+
+    1.0 - row[b.len()] as f32 / longest as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rspotify::prelude::Id;
+
+    const ID: &str = "37i9dQZF1DXcBWIGoYBM5M";
+
+    #[test]
+    fn test_normalize_bare_id() {
+        assert_eq!(normalize_id(ID).as_deref(), Some(ID));
+        assert_eq!(
+            normalize_id("  37i9dQZF1DXcBWIGoYBM5M\n").as_deref(),
+            Some(ID)
+        );
+    }
+
+    #[test]
+    fn test_normalize_uri() {
+        let playlist = format!("spotify:playlist:{}", ID);
+        let track = format!("spotify:track:{}", ID);
+        assert_eq!(normalize_id(&playlist).as_deref(), Some(ID));
+        assert_eq!(normalize_id(&track).as_deref(), Some(ID));
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        let plain = format!("https://open.spotify.com/playlist/{}", ID);
+        let tracking = format!("https://open.spotify.com/track/{}?si=abc123&utm=x", ID);
+        let localized = format!("https://open.spotify.com/intl-es/track/{}", ID);
+        assert_eq!(normalize_id(&plain).as_deref(), Some(ID));
+        assert_eq!(normalize_id(&tracking).as_deref(), Some(ID));
+        assert_eq!(normalize_id(&localized).as_deref(), Some(ID));
+    }
+
+    #[test]
+    fn test_normalize_invalid() {
+        assert_eq!(normalize_id(""), None);
+        assert_eq!(normalize_id("not an id"), None);
+        assert_eq!(normalize_id("spotify:album:abc"), None);
+        assert_eq!(normalize_id("spotify:playlist:"), None);
+        assert_eq!(normalize_id("https://example.com/playlist/abc"), None);
+        assert_eq!(normalize_id("https://open.spotify.com/artist/abc"), None);
+    }
+
+    #[test]
+    fn test_parse_playlist_id_shapes() {
+        let inputs = [
+            ID.to_string(),
+            format!("spotify:playlist:{}", ID),
+            format!("https://open.spotify.com/playlist/{}", ID),
+            format!("https://open.spotify.com/playlist/{}?si=abc123", ID),
+            format!("https://open.spotify.com/intl-de/playlist/{}", ID),
+        ];
+        for input in &inputs {
+            let id = parse_playlist_id(input).unwrap_or_else(|| panic!("rejected {}", input));
+            assert_eq!(id.id(), ID);
+        }
+    }
+
+    #[test]
+    fn test_parse_playlist_url() {
+        let urls = [
+            format!("https://open.spotify.com/playlist/{}", ID),
+            format!("https://open.spotify.com/playlist/{}/", ID),
+            format!(
+                "https://open.spotify.com/playlist/{}?si=4f2c1e9a0b7d4e31",
+                ID
+            ),
+            format!(
+                "https://open.spotify.com/playlist/{}?si=abc&utm_source=copy-link",
+                ID
+            ),
+            format!("http://open.spotify.com/intl-es/playlist/{}#top", ID),
+            format!("  https://open.spotify.com/playlist/{}?pi=u-xyz  ", ID),
+        ];
+        for url in &urls {
+            let id = parse_playlist_url(url).unwrap_or_else(|| panic!("rejected {}", url));
+            assert_eq!(id.id(), ID);
+        }
+
+        let rejected = [
+            ID.to_string(),
+            format!("spotify:playlist:{}", ID),
+            format!("https://open.spotify.com/track/{}?si=abc", ID),
+            format!("https://open.spotify.com/album/{}", ID),
+            "https://open.spotify.com/playlist/".to_string(),
+            format!("https://example.com/playlist/{}", ID),
+        ];
+        for input in &rejected {
+            assert!(parse_playlist_url(input).is_none(), "accepted {}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_track_id_shapes() {
+        let inputs = [
+            ID.to_string(),
+            format!("spotify:track:{}", ID),
+            format!("https://open.spotify.com/track/{}", ID),
+            format!("https://open.spotify.com/track/{}?si=abc123#t=10", ID),
+            format!("https://open.spotify.com/intl-es/track/{}", ID),
+        ];
+        for input in &inputs {
+            let id = parse_track_id(input).unwrap_or_else(|| panic!("rejected {}", input));
+            assert_eq!(id.id(), ID);
+        }
+    }
+
+    #[test]
+    fn test_parse_id_rejects_wrong_kind() {
+        let track_uri = format!("spotify:track:{}", ID);
+        let track_url = format!("https://open.spotify.com/track/{}", ID);
+        let playlist_uri = format!("spotify:playlist:{}", ID);
+        let playlist_url = format!("https://open.spotify.com/playlist/{}", ID);
+        assert!(parse_playlist_id(&track_uri).is_none());
+        assert!(parse_playlist_id(&track_url).is_none());
+        assert!(parse_track_id(&playlist_uri).is_none());
+        assert!(parse_track_id(&playlist_url).is_none());
+        assert!(parse_playlist_id("not an id").is_none());
+        assert!(parse_track_id("").is_none());
+    }
+
+    #[test]
+    fn test_name_similarity() {
+        assert_eq!(name_similarity("yesterday", "yesterday"), 1.0);
+        assert_eq!(name_similarity("", ""), 1.0);
+        assert_eq!(name_similarity("abc", ""), 0.0);
+        assert_eq!(name_similarity("kitten", "sitting"), 1.0 - 3.0 / 7.0);
+        assert!(name_similarity("song (remastered)", "song (remaster)") > 0.8);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(
+            parse_date("2025-01-31"),
+            NaiveDate::from_ymd_opt(2025, 1, 31)
+        );
+        assert_eq!(
+            parse_date(" 2024-02-29 "),
+            NaiveDate::from_ymd_opt(2024, 2, 29)
+        );
+        assert_eq!(parse_date("2025-02-30"), None);
+        assert_eq!(parse_date("31/01/2025"), None);
+    }
+
+    #[test]
+    fn test_parse_market() {
+        assert_eq!(
+            parse_market("US"),
+            Some(Market::Country(Country::UnitedStates))
+        );
+        assert_eq!(parse_market("mx"), Some(Market::Country(Country::Mexico)));
+        assert_eq!(parse_market("XX"), None);
+        assert_eq!(parse_market("USA"), None);
+        assert_eq!(parse_market(""), None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "00:00:00");
+        assert_eq!(format_duration(3_725_999), "01:02:05");
+        assert_eq!(format_duration(360_000_000), "100:00:00");
+    }
+}